@@ -0,0 +1,70 @@
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::TrustDnsResolver;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+const NAME: &str = "one.one.one.one";
+const CONCURRENT_CALLERS: usize = 64;
+
+async fn lookup(mut resolver: TrustDnsResolver) {
+    let name: Name = NAME.parse().unwrap();
+
+    assert!(!resolver.call(name).await.unwrap().is_empty());
+}
+
+fn cold_lookup(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let _guard = rt.enter();
+
+    c.bench_function("cold_lookup", |b| {
+        b.to_async(&rt)
+            .iter_batched(TrustDnsResolver::new, lookup, BatchSize::SmallInput);
+    });
+}
+
+fn warm_lookup(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let _guard = rt.enter();
+
+    let resolver = TrustDnsResolver::new();
+    rt.block_on(lookup(resolver.clone()));
+
+    c.bench_function("warm_lookup", |b| {
+        b.to_async(&rt).iter(|| lookup(resolver.clone()));
+    });
+}
+
+fn concurrent_lookups(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let _guard = rt.enter();
+
+    let resolver = TrustDnsResolver::new();
+    rt.block_on(lookup(resolver.clone()));
+
+    let mut group = c.benchmark_group("concurrent_lookups");
+    group.throughput(Throughput::Elements(CONCURRENT_CALLERS as u64));
+    group.bench_function("warm", |b| {
+        b.to_async(&rt).iter(|| async {
+            let lookups: Vec<_> = (0..CONCURRENT_CALLERS)
+                .map(|_| tokio::spawn(lookup(resolver.clone())))
+                .collect();
+
+            for lookup in lookups {
+                lookup.await.unwrap();
+            }
+        });
+    });
+    group.finish();
+}
+
+criterion_group!(benches, cold_lookup, warm_lookup, concurrent_lookups);
+criterion_main!(benches);
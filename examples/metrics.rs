@@ -0,0 +1,46 @@
+//! Serve the metrics of a resolver for Prometheus to scrape.
+//!
+//! Run with `cargo run --example metrics --features metrics` and open
+//! <http://127.0.0.1:3000/metrics>.
+
+use axum::{extract::State, routing::get, Router};
+use hyper::{Body, Client, Request};
+use hyper_trust_dns::{TrustDnsMetrics, TrustDnsResolver};
+use prometheus::{Encoder, Registry, TextEncoder};
+
+async fn serve_metrics(State(registry): State<Registry>) -> String {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&registry.gather(), &mut buffer)
+        .unwrap();
+
+    String::from_utf8(buffer).unwrap()
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    let registry = Registry::new();
+    let metrics = TrustDnsMetrics::new(&registry).unwrap();
+
+    let connector = TrustDnsResolver::builder()
+        .metrics(metrics)
+        .build()
+        .unwrap()
+        .into_rustls_webpki_https_connector();
+    let client: Client<_> = Client::builder().build(connector);
+
+    let request = Request::builder()
+        .uri("https://www.google.com/")
+        .body(Body::empty())
+        .unwrap();
+    let _ = client.request(request).await;
+
+    let app = Router::new()
+        .route("/metrics", get(serve_metrics))
+        .with_state(registry);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:3000")
+        .await
+        .unwrap();
+
+    axum::serve(listener, app).await.unwrap();
+}
@@ -0,0 +1,170 @@
+use std::{
+    fmt, io,
+    net::{IpAddr, SocketAddr, ToSocketAddrs},
+    time::Instant,
+    vec,
+};
+
+/// Iterator over DNS lookup results.
+///
+/// Cloning this iterator is cheap and yields the remaining addresses without
+/// consuming the original.
+#[derive(Clone)]
+pub struct SocketAddrs {
+    iter: vec::IntoIter<IpAddr>,
+    /// When the records the addresses were resolved from expire, if known.
+    valid_until: Option<Instant>,
+    /// The port of the returned socket addresses.
+    port: u16,
+}
+
+/// The order in which the addresses of a lookup are returned.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AddrOrder {
+    /// Keep the order returned by the resolver.
+    Unchanged,
+    /// Interleave IPv6 and IPv4 addresses as described in RFC 6555, starting
+    /// with IPv6 unless `ipv6_first` is false.
+    Interleaved { ipv6_first: bool },
+    /// Return all IPv4 addresses before the IPv6 addresses.
+    Ipv4First,
+}
+
+impl SocketAddrs {
+    /// Create a new [`SocketAddrs`] from the resolved IP addresses.
+    pub(crate) fn new(ips: impl IntoIterator<Item = IpAddr>, order: AddrOrder) -> Self {
+        let ips: Vec<IpAddr> = ips.into_iter().collect();
+
+        let ips = match order {
+            AddrOrder::Unchanged => ips,
+            AddrOrder::Interleaved { ipv6_first } => interleave(ips, ipv6_first),
+            AddrOrder::Ipv4First => {
+                let (mut ipv4, ipv6): (Vec<_>, Vec<_>) = ips.into_iter().partition(IpAddr::is_ipv4);
+                ipv4.extend(ipv6);
+
+                ipv4
+            }
+        };
+
+        Self {
+            iter: ips.into_iter(),
+            valid_until: None,
+            port: 0,
+        }
+    }
+
+    /// Set when the records the addresses were resolved from expire.
+    pub(crate) fn with_valid_until(mut self, valid_until: Option<Instant>) -> Self {
+        self.valid_until = valid_until;
+
+        self
+    }
+
+    /// Set the port of the returned socket addresses, which is 0 by default.
+    pub(crate) fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+
+        self
+    }
+
+    /// When the records the addresses were resolved from expire, if known.
+    pub(crate) fn valid_until(&self) -> Option<Instant> {
+        self.valid_until
+    }
+
+    /// Collect the remaining addresses into a [`Vec`].
+    #[must_use]
+    pub fn into_vec(self) -> Vec<SocketAddr> {
+        self.collect()
+    }
+
+    /// Returns `true` if there are no addresses left.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.iter.as_slice().is_empty()
+    }
+}
+
+impl Iterator for SocketAddrs {
+    type Item = SocketAddr;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|ip_addr| SocketAddr::new(ip_addr, self.port))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl ExactSizeIterator for SocketAddrs {
+    fn len(&self) -> usize {
+        self.iter.len()
+    }
+}
+
+impl fmt::Debug for SocketAddrs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SocketAddrs")
+            .field("remaining", &self.iter.as_slice())
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<SocketAddrs> for Vec<SocketAddr> {
+    fn from(addrs: SocketAddrs) -> Self {
+        addrs.into_vec()
+    }
+}
+
+/// The remaining addresses of a [`SocketAddrs`] as a [`ToSocketAddrs`], to
+/// pass them to `std` networking functions like
+/// [`TcpStream::connect`](std::net::TcpStream::connect).
+#[derive(Debug, Clone)]
+pub struct SocketAddrList(SocketAddrs);
+
+impl From<SocketAddrs> for SocketAddrList {
+    fn from(addrs: SocketAddrs) -> Self {
+        Self(addrs)
+    }
+}
+
+impl ToSocketAddrs for SocketAddrList {
+    type Iter = SocketAddrs;
+
+    fn to_socket_addrs(&self) -> io::Result<Self::Iter> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Interleave IPv6 and IPv4 addresses, starting with the given family.
+/// The relative order of addresses within a family is kept.
+fn interleave(ips: Vec<IpAddr>, ipv6_first: bool) -> Vec<IpAddr> {
+    let (first, second): (Vec<_>, Vec<_>) = ips
+        .iter()
+        .copied()
+        .partition(|ip| ip.is_ipv6() == ipv6_first);
+
+    // Nothing to interleave with only a single address family
+    if first.is_empty() || second.is_empty() {
+        return ips;
+    }
+
+    let mut first = first.into_iter();
+    let mut second = second.into_iter();
+    let mut interleaved = Vec::with_capacity(ips.len());
+
+    loop {
+        match (first.next(), second.next()) {
+            (None, None) => break,
+            (a, b) => {
+                interleaved.extend(a);
+                interleaved.extend(b);
+            }
+        }
+    }
+
+    interleaved
+}
@@ -0,0 +1,250 @@
+//! A circuit breaker around a resolver.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+
+use crate::{
+    resolver::error::ResolveErrorKind,
+    service::{impl_service, Resolve},
+    DnsConnectError, ResolveError, TrustDnsResolver,
+};
+
+/// A resolver that stops querying a [`TrustDnsResolver`] after repeated
+/// failures.
+///
+/// The circuit starts out closed and lookups go to the inner resolver. After
+/// `failure_threshold` consecutive failed lookups, the circuit opens and all
+/// lookups fail immediately for `open_duration`. Afterwards the circuit is
+/// half-open: a single probe lookup at a time is sent to the inner resolver
+/// while the others keep failing, and `success_threshold` consecutive
+/// successful probes close the circuit, while a single failure opens it again.
+///
+/// Only timeouts, unreachable nameservers and I/O or protocol errors count as
+/// failures. Names that do not exist or have no addresses were answered by a
+/// healthy nameserver and count as successes.
+///
+/// Clones share the same circuit.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerResolver {
+    inner: TrustDnsResolver,
+    policy: Policy,
+    state: Arc<Mutex<State>>,
+}
+
+/// The thresholds of a [`CircuitBreakerResolver`].
+#[derive(Debug, Clone, Copy)]
+struct Policy {
+    failure_threshold: u32,
+    success_threshold: u32,
+    open_duration: Duration,
+}
+
+/// The state of the circuit.
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    HalfOpen { successes: u32, probing: bool },
+}
+
+impl CircuitBreakerResolver {
+    /// Create a new [`CircuitBreakerResolver`] around `inner`.
+    ///
+    /// By default, the circuit opens after 5 consecutive failures, stays open
+    /// for 30 seconds and closes after a single successful probe.
+    #[must_use]
+    pub fn new(inner: TrustDnsResolver) -> Self {
+        Self {
+            inner,
+            policy: Policy {
+                failure_threshold: 5,
+                success_threshold: 1,
+                open_duration: Duration::from_secs(30),
+            },
+            state: Arc::new(Mutex::new(State::Closed { failures: 0 })),
+        }
+    }
+
+    /// Open the circuit after `failure_threshold` consecutive failures.
+    #[must_use]
+    pub fn failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.policy.failure_threshold = failure_threshold.max(1);
+
+        self
+    }
+
+    /// Close the half-open circuit after `success_threshold` consecutive
+    /// successes.
+    #[must_use]
+    pub fn success_threshold(mut self, success_threshold: u32) -> Self {
+        self.policy.success_threshold = success_threshold.max(1);
+
+        self
+    }
+
+    /// Keep the circuit open for `open_duration` before probing the inner
+    /// resolver again.
+    #[must_use]
+    pub fn open_duration(mut self, open_duration: Duration) -> Self {
+        self.policy.open_duration = open_duration;
+
+        self
+    }
+
+    /// Returns `true` if the circuit is currently open and lookups fail
+    /// without querying the inner resolver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the state lock was poisoned.
+    #[must_use]
+    pub fn is_open(&self) -> bool {
+        matches!(*self.state.lock().unwrap(), State::Open { until } if Instant::now() < until)
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+}
+
+/// Fail a lookup without querying the inner resolver.
+fn reject(message: String) -> <CircuitBreakerResolver as Service<Name>>::Future {
+    Box::pin(async move { Err(ResolveError::from(message).into()) })
+}
+
+/// Whether `error` means that the nameservers could not be reached or did not
+/// answer properly. Other errors, like non-existent names, are answers of
+/// healthy nameservers and do not count as failures.
+fn is_upstream_failure(error: &DnsConnectError) -> bool {
+    matches!(
+        error.kind(),
+        ResolveErrorKind::Timeout
+            | ResolveErrorKind::NoConnections
+            | ResolveErrorKind::Io(_)
+            | ResolveErrorKind::Proto(_)
+    )
+}
+
+/// Updates the state of the circuit when a lookup completes or is cancelled.
+struct Outcome {
+    state: Arc<Mutex<State>>,
+    policy: Policy,
+    probe: bool,
+    failed: Option<bool>,
+}
+
+impl Outcome {
+    fn record(&mut self, failed: bool) {
+        self.failed = Some(failed);
+    }
+}
+
+impl Drop for Outcome {
+    fn drop(&mut self) {
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        let policy = self.policy;
+
+        *state = match (*state, self.failed) {
+            // A cancelled probe lets the next lookup probe instead
+            (State::HalfOpen { successes, .. }, None) if self.probe => State::HalfOpen {
+                successes,
+                probing: false,
+            },
+            (state, None) => state,
+            // Only the probe decides whether a half-open circuit closes, and
+            // lookups that started before the circuit opened do not cut the
+            // cool-down short
+            (state @ (State::HalfOpen { .. } | State::Open { .. }), Some(_)) if !self.probe => {
+                state
+            }
+            (State::HalfOpen { successes, .. }, Some(false))
+                if successes + 1 < policy.success_threshold =>
+            {
+                State::HalfOpen {
+                    successes: successes + 1,
+                    probing: false,
+                }
+            }
+            (_, Some(false)) => State::Closed { failures: 0 },
+            (State::Closed { failures }, Some(true)) if failures + 1 < policy.failure_threshold => {
+                State::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (_, Some(true)) => State::Open {
+                until: Instant::now() + policy.open_duration,
+            },
+        };
+    }
+}
+
+impl Resolve for CircuitBreakerResolver {
+    type Future = <TrustDnsResolver as Service<Name>>::Future;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        let name = name.to_owned();
+
+        let probe = {
+            let mut state = self.state.lock().unwrap();
+
+            match *state {
+                State::Closed { .. } => false,
+                State::Open { until } if Instant::now() < until => {
+                    return reject(format!("circuit breaker is open, not looking up {name}"));
+                }
+                State::HalfOpen { probing: true, .. } => {
+                    return reject(format!(
+                        "circuit breaker is half-open and probing, not looking up {name}"
+                    ));
+                }
+                State::Open { .. } => {
+                    *state = State::HalfOpen {
+                        successes: 0,
+                        probing: true,
+                    };
+
+                    true
+                }
+                State::HalfOpen {
+                    successes,
+                    probing: false,
+                } => {
+                    *state = State::HalfOpen {
+                        successes,
+                        probing: true,
+                    };
+
+                    true
+                }
+            }
+        };
+
+        let lookup = self.inner.resolve(name);
+        let mut outcome = Outcome {
+            state: self.state.clone(),
+            policy: self.policy,
+            probe,
+            failed: None,
+        };
+
+        Box::pin(async move {
+            let result = lookup.await;
+            outcome.record(matches!(&result, Err(e) if is_upstream_failure(e)));
+
+            result
+        })
+    }
+}
+
+impl_service!(CircuitBreakerResolver);
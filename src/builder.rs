@@ -0,0 +1,478 @@
+use std::{error::Error, fmt, mem, net::SocketAddr, str::FromStr, sync::Arc, time::Duration};
+
+use ipnet::IpNet;
+
+use crate::{
+    ecs::{self, ClientSubnetLookup},
+    lookup::LookupSettings,
+    resolver::{
+        config::{LookupIpStrategy, Protocol, ResolverConfig, ResolverOpts},
+        Name, TokioAsyncResolver,
+    },
+    TrustDnsResolver,
+};
+
+/// A builder for a [`TrustDnsResolver`].
+///
+/// The builder starts out with the default [`ResolverConfig`] and
+/// [`ResolverOpts`], which can be replaced wholesale with
+/// [`TrustDnsResolverBuilder::config`] and [`TrustDnsResolverBuilder::options`]
+/// or adjusted with the individual setters.
+#[derive(Clone, Default)]
+#[must_use]
+pub struct TrustDnsResolverBuilder {
+    config: ResolverConfig,
+    options: ResolverOpts,
+    ipv4_only: bool,
+    ipv6_only: bool,
+    search_domains: Vec<String>,
+    client_subnet: Option<IpNet>,
+    tcp_fallback: Option<bool>,
+    settings: LookupSettings,
+}
+
+impl TrustDnsResolverBuilder {
+    /// Create a new [`TrustDnsResolverBuilder`] with the default config
+    /// options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the [`ResolverConfig`] to use.
+    pub fn config(mut self, config: ResolverConfig) -> Self {
+        self.config = config;
+
+        self
+    }
+
+    /// Set the [`ResolverOpts`] to use.
+    /// This replaces all options set previously on this builder.
+    pub fn options(mut self, options: ResolverOpts) -> Self {
+        self.options = options;
+
+        self
+    }
+
+    /// Set the maximum number of entries in the resolver's cache.
+    pub fn cache_size(mut self, cache_size: usize) -> Self {
+        self.options.cache_size = cache_size;
+
+        self
+    }
+
+    /// Add `domains` to the search list of the [`ResolverConfig`].
+    ///
+    /// Relative names with fewer dots than [`TrustDnsResolverBuilder::ndots`]
+    /// are first looked up with each search domain appended, in order, and
+    /// only then as they are. For example, with the search domain
+    /// `svc.cluster.local`, `api` is looked up as `api.svc.cluster.local`
+    /// first. Names ending with a dot are absolute and never use the search
+    /// list. The domains are added to those of the config, like the ones read
+    /// from the `search` line of `/etc/resolv.conf`.
+    pub fn search_domains(mut self, domains: Vec<String>) -> Self {
+        self.search_domains.extend(domains);
+
+        self
+    }
+
+    /// Set the number of dots a name needs to have to be looked up as it is
+    /// before trying the search domains. Defaults to 1, Kubernetes uses 5.
+    pub fn ndots(mut self, ndots: u8) -> Self {
+        self.options.ndots = usize::from(ndots);
+
+        self
+    }
+
+    /// Set the maximum time a successful lookup is cached for, regardless of
+    /// the TTL of its records.
+    pub fn max_ttl(mut self, max_ttl: Duration) -> Self {
+        self.options.positive_max_ttl = Some(max_ttl);
+
+        self
+    }
+
+    /// Set the minimum time a successful lookup is cached for, even if the TTL
+    /// of its records is lower. This avoids sending a query for every
+    /// connection to names with a TTL of 0.
+    pub fn min_ttl(mut self, min_ttl: Duration) -> Self {
+        self.options.positive_min_ttl = Some(min_ttl);
+
+        self
+    }
+
+    /// Cache responses for non-existent names (`NXDOMAIN`) and names without
+    /// addresses for `ttl`, regardless of the TTL of the SOA record in the
+    /// response, so that repeated lookups of these names do not reach the
+    /// nameservers.
+    ///
+    /// Responses without an SOA record are never cached by the resolver
+    /// backend. Defaults to the TTL of the SOA record.
+    pub fn negative_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.options.negative_min_ttl = Some(ttl);
+        self.options.negative_max_ttl = Some(ttl);
+
+        self
+    }
+
+    /// Set the timeout for lookups.
+    ///
+    /// This is used both as the timeout for a single request to a nameserver
+    /// and as the deadline for each lookup attempt of the resolver service,
+    /// which also covers the attempts made by the resolver itself. Replacing
+    /// the options with [`TrustDnsResolverBuilder::options`] afterwards keeps
+    /// the deadline.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.options.timeout = timeout;
+        self.settings.timeout = Some(timeout);
+
+        self
+    }
+
+    /// Set the number of times the resolver service retries a lookup that
+    /// failed with a transient error, such as a timeout or no available
+    /// connections. Other errors, like a non-existent domain, are returned
+    /// immediately. Defaults to 0.
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.settings.max_retries = max_retries;
+
+        self
+    }
+
+    /// Set the delay before the first retry of a lookup, which doubles with
+    /// every further retry. Defaults to 100 milliseconds.
+    pub fn backoff(mut self, backoff: Duration) -> Self {
+        self.settings.backoff = backoff;
+
+        self
+    }
+
+    /// Set the number of attempts made before giving up on a request.
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.options.attempts = attempts;
+
+        self
+    }
+
+    /// Set whether queries are retried over TCP when the UDP response is
+    /// truncated or the UDP query fails.
+    ///
+    /// Enabling this retries failed UDP queries over TCP too, which the
+    /// resolver backend only does for truncated responses by default.
+    /// Disabling it removes the TCP nameservers that duplicate a UDP
+    /// nameserver of the config, so that each query takes a single round
+    /// trip. A truncated response is then returned as it is: it may contain
+    /// only some of the addresses of the name or none at all, in which case
+    /// the lookup fails as if the name had no addresses. Nameservers that are
+    /// only configured for TCP are kept.
+    pub fn tcp_fallback(mut self, enabled: bool) -> Self {
+        self.tcp_fallback = Some(enabled);
+
+        self
+    }
+
+    /// Query the nameservers of the config in a random order, chosen anew for
+    /// each query, to spread the load across replicas of a nameserver.
+    ///
+    /// By default, the resolver backend prefers the nameservers that answered
+    /// fastest and most reliably before. Shuffling replaces that ordering, so
+    /// slow nameservers are queried as often as fast ones. Disabled by
+    /// default.
+    pub fn shuffle_dns_servers(mut self, enabled: bool) -> Self {
+        self.options.shuffle_dns_servers = enabled;
+
+        self
+    }
+
+    /// Validate the responses to lookups with DNSSEC.
+    ///
+    /// The responses are validated against the root trust anchor that is
+    /// built into the resolver backend. Responses that fail validation are
+    /// returned as a [`ResolveErrorKind::Proto`] error, unlike non-existent
+    /// domains, which are [`ResolveErrorKind::NoRecordsFound`]. Disabled by
+    /// default.
+    ///
+    /// [`ResolveErrorKind::Proto`]: crate::resolver::error::ResolveErrorKind::Proto
+    /// [`ResolveErrorKind::NoRecordsFound`]: crate::resolver::error::ResolveErrorKind::NoRecordsFound
+    #[cfg(any(feature = "dnssec-openssl", feature = "dnssec-ring"))]
+    pub fn dnssec_validation(mut self, enabled: bool) -> Self {
+        self.options.validate = enabled;
+
+        self
+    }
+
+    /// Set the [`LookupIpStrategy`] that determines which address families are
+    /// looked up. This is overridden by [`TrustDnsResolverBuilder::ipv4_only`]
+    /// and [`TrustDnsResolverBuilder::ipv6_only`].
+    pub fn ip_strategy(mut self, ip_strategy: LookupIpStrategy) -> Self {
+        self.options.ip_strategy = ip_strategy;
+
+        self
+    }
+
+    /// Only look up IPv4 (A) addresses, without ever sending AAAA queries.
+    /// This cannot be combined with [`TrustDnsResolverBuilder::ipv6_only`].
+    pub fn ipv4_only(mut self, enabled: bool) -> Self {
+        self.ipv4_only = enabled;
+
+        self
+    }
+
+    /// Only look up IPv6 (AAAA) addresses.
+    /// This cannot be combined with [`TrustDnsResolverBuilder::ipv4_only`].
+    pub fn ipv6_only(mut self, enabled: bool) -> Self {
+        self.ipv6_only = enabled;
+
+        self
+    }
+
+    /// Interleave IPv6 and IPv4 addresses in the lookup results, starting
+    /// with IPv6, as recommended by RFC 6555 (Happy Eyeballs).
+    ///
+    /// This only has an effect if a lookup returns addresses of both families,
+    /// which depends on the [`LookupIpStrategy`] in use. Enabled by default.
+    pub fn happy_eyeballs(mut self, enabled: bool) -> Self {
+        self.settings.happy_eyeballs = enabled;
+
+        self
+    }
+
+    /// Return all IPv4 addresses of a lookup before the IPv6 addresses, for
+    /// example on dual-stack hosts with unreliable IPv6 connectivity. The
+    /// relative order within each family is kept.
+    ///
+    /// This takes precedence over [`TrustDnsResolverBuilder::happy_eyeballs`].
+    /// Disabled by default.
+    pub fn prefer_ipv4(mut self, enabled: bool) -> Self {
+        self.settings.prefer_ipv4 = enabled;
+
+        self
+    }
+
+    /// Send the A and AAAA queries of a lookup concurrently instead of
+    /// leaving them to the resolver backend.
+    ///
+    /// With [`LookupIpStrategy::Ipv4AndIpv6`], the addresses of whichever
+    /// query is answered first are returned before those of the slower one.
+    /// The slower query only gets another 50 ms to be answered, so a hanging
+    /// query does not delay the lookup. With [`LookupIpStrategy::Ipv4thenIpv6`]
+    /// and [`LookupIpStrategy::Ipv6thenIpv4`], the second address family is
+    /// queried right away, but only used if the first one has no addresses.
+    /// The `*Only` strategies only send the query of their address family.
+    ///
+    /// With [`TrustDnsResolverBuilder::happy_eyeballs`], the interleaving
+    /// starts with the address family that was answered first. Note that these
+    /// queries bypass the hosts file and search domains. Disabled by default.
+    pub fn race_queries(mut self, enabled: bool) -> Self {
+        self.settings.race_queries = enabled;
+
+        self
+    }
+
+    /// Remove addresses in private, loopback, link-local and other reserved
+    /// ranges from the lookup results, to protect against DNS rebinding
+    /// attacks. IPv6 addresses that embed an IPv4 address, like IPv4-mapped,
+    /// NAT64 and 6to4 addresses, are checked by the embedded address as well.
+    /// Lookups that only return such addresses fail. This also applies to
+    /// names that are IP addresses. Disabled by default.
+    pub fn block_private_ips(mut self, enabled: bool) -> Self {
+        self.settings.block_private_ips = enabled;
+
+        self
+    }
+
+    /// Send the EDNS Client Subnet option (RFC 7871) with `subnet` in the
+    /// queries of lookups, so that nameservers that support it can return
+    /// addresses close to the client, for example of a CDN.
+    ///
+    /// **This discloses the subnet to the nameservers and every
+    /// authoritative server they forward it to.** Use a prefix that is only
+    /// as precise as necessary, RFC 7871 recommends 24 bits for IPv4 and 56
+    /// bits for IPv6, and never send the subnet of a client that did not
+    /// agree to it. The host bits of `subnet` are cleared.
+    ///
+    /// The resolver backend cannot add the option to its own queries, so
+    /// these queries are sent directly to the nameservers of the config that
+    /// are queried via plain UDP or TCP, building fails with
+    /// [`BuildError::NoUnencryptedNameServers`] if there are none. Truncated
+    /// UDP responses are retried via TCP if the nameserver is configured for
+    /// it. The queries bypass the cache, the hosts file and the search
+    /// domains and the [`ResolverOpts`] only apply as far as the timeout and
+    /// the [`LookupIpStrategy`] are concerned.
+    pub fn edns_client_subnet(mut self, subnet: IpNet) -> Self {
+        self.client_subnet = Some(subnet);
+
+        self
+    }
+
+    /// Set the domain that is looked up by
+    /// [`TrustDnsResolver::health_check`]. Defaults to `one.one.one.one`.
+    pub fn health_check_domain(mut self, domain: impl Into<String>) -> Self {
+        self.settings.health_check_domain = domain.into();
+
+        self
+    }
+
+    /// Record the duration and errors of lookups in `metrics`.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(mut self, metrics: crate::TrustDnsMetrics) -> Self {
+        self.settings.metrics = Some(metrics);
+
+        self
+    }
+
+    /// Build the [`TrustDnsResolver`].
+    /// This must be run inside a Tokio runtime context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if mutually exclusive settings were enabled, a search
+    /// domain is not a valid domain name or the config has no nameservers.
+    pub fn build(self) -> Result<TrustDnsResolver, BuildError> {
+        if self.ipv4_only && self.ipv6_only {
+            return Err(BuildError::ConflictingIpStrategies);
+        }
+
+        if self
+            .search_domains
+            .iter()
+            .any(|domain| Name::from_str(domain).is_err())
+        {
+            return Err(BuildError::InvalidSearchDomain);
+        }
+
+        if self.client_subnet.is_some() && !ecs::has_plain_name_servers(&self.config) {
+            return Err(BuildError::NoUnencryptedNameServers);
+        }
+
+        if self.config.name_servers().is_empty() {
+            return Err(BuildError::NoNameServers);
+        }
+
+        Ok(self.build_unchecked())
+    }
+
+    /// Build the [`TrustDnsResolver`] without validating the settings.
+    pub(crate) fn build_unchecked(mut self) -> TrustDnsResolver {
+        if self.ipv4_only {
+            self.options.ip_strategy = LookupIpStrategy::Ipv4Only;
+        } else if self.ipv6_only {
+            self.options.ip_strategy = LookupIpStrategy::Ipv6Only;
+        }
+
+        let mut config = mem::take(&mut self.config);
+        for domain in &self.search_domains {
+            if let Ok(domain) = Name::from_str(domain) {
+                config.add_search(domain);
+            }
+        }
+
+        if let Some(enabled) = self.tcp_fallback {
+            self.options.try_tcp_on_error = enabled;
+
+            if !enabled {
+                config = without_tcp_fallback(&config);
+            }
+        }
+
+        let options = mem::take(&mut self.options);
+        self.settings.ip_strategy = options.ip_strategy;
+        let name_servers = name_server_addrs(&config);
+
+        if let Some(subnet) = self.client_subnet {
+            self.settings.client_subnet = Some(Arc::new(ClientSubnetLookup::new(
+                subnet,
+                &config,
+                options.ip_strategy,
+                options.timeout,
+            )));
+        }
+
+        let resolver = TokioAsyncResolver::tokio(config, options);
+
+        TrustDnsResolver {
+            name_servers: Some(name_servers),
+            ..self.build_with_resolver(Arc::new(resolver))
+        }
+    }
+
+    /// Build the [`TrustDnsResolver`] around an existing resolver, ignoring
+    /// the config and options of this builder.
+    pub(crate) fn build_with_resolver(self, resolver: Arc<TokioAsyncResolver>) -> TrustDnsResolver {
+        TrustDnsResolver {
+            resolver,
+            settings: Arc::new(self.settings),
+            name_servers: None,
+            on_resolve: None,
+            shutdown: None,
+        }
+    }
+}
+
+/// Remove the TCP nameservers of a [`ResolverConfig`] that have a UDP
+/// nameserver with the same address, which the resolver backend falls back
+/// to.
+fn without_tcp_fallback(config: &ResolverConfig) -> ResolverConfig {
+    let name_servers = config.name_servers();
+    let name_servers = name_servers
+        .iter()
+        .filter(|name_server| {
+            name_server.protocol != Protocol::Tcp
+                || !name_servers.iter().any(|other| {
+                    other.protocol == Protocol::Udp && other.socket_addr == name_server.socket_addr
+                })
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+
+    ResolverConfig::from_parts(
+        config.domain().cloned(),
+        config.search().to_vec(),
+        name_servers,
+    )
+}
+
+/// Collect the distinct addresses of the nameservers in a [`ResolverConfig`].
+/// Most configs list every address once per protocol.
+fn name_server_addrs(config: &ResolverConfig) -> Arc<[SocketAddr]> {
+    let mut addrs: Vec<SocketAddr> = Vec::new();
+
+    for name_server in config.name_servers() {
+        if !addrs.contains(&name_server.socket_addr) {
+            addrs.push(name_server.socket_addr);
+        }
+    }
+
+    addrs.into()
+}
+
+/// An error returned when building a [`TrustDnsResolver`] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// Both IPv4-only and IPv6-only lookups were requested.
+    ConflictingIpStrategies,
+    /// One of the search domains is not a valid domain name.
+    InvalidSearchDomain,
+    /// The EDNS Client Subnet option was requested, but the config has no
+    /// nameservers that are queried via plain UDP or TCP.
+    NoUnencryptedNameServers,
+    /// The config has no nameservers, so no name could be looked up.
+    NoNameServers,
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ConflictingIpStrategies => {
+                f.write_str("ipv4_only and ipv6_only are mutually exclusive")
+            }
+            Self::InvalidSearchDomain => f.write_str("invalid search domain"),
+            Self::NoUnencryptedNameServers => {
+                f.write_str("edns_client_subnet requires nameservers queried via UDP or TCP")
+            }
+            Self::NoNameServers => f.write_str("no nameservers configured"),
+        }
+    }
+}
+
+impl Error for BuildError {}
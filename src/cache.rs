@@ -0,0 +1,307 @@
+//! An application-level cache in front of a [`TrustDnsResolver`].
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+
+use crate::{
+    service::{impl_service, Resolve},
+    SocketAddrs, TrustDnsResolver,
+};
+
+/// A [`TrustDnsResolver`] with an additional cache of lookup results.
+///
+/// Cached results are served without going through the resolver at all.
+/// Entries expire when the TTL of their records runs out, but no later than
+/// the maximum TTL of the cache. Once the cache is full, expired entries and
+/// then the entries closest to expiry are evicted to make room.
+///
+/// Clones of this resolver share the same cache.
+#[derive(Debug, Clone)]
+pub struct CachingTrustDnsResolver {
+    resolver: TrustDnsResolver,
+    cache: Arc<Cache>,
+    policy: CachePolicy,
+}
+
+/// Statistics about the cache of a [`CachingTrustDnsResolver`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CacheStats {
+    /// The number of lookups that were answered from the cache, including
+    /// those answered with stale entries.
+    pub hits: u64,
+    /// The number of lookups that were answered with stale entries.
+    pub stale_hits: u64,
+    /// The number of lookups that went to the resolver.
+    pub misses: u64,
+    /// The number of entries currently in the cache, including expired ones
+    /// that were not evicted yet.
+    pub size: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CachePolicy {
+    max_ttl: Duration,
+    capacity: usize,
+    stale_for: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Cache {
+    entries: Mutex<HashMap<String, Entry>>,
+    hits: AtomicU64,
+    stale_hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    addrs: SocketAddrs,
+    /// When the entry expires, or `None` if the maximum TTL is too large to
+    /// be represented, so that it never does.
+    expires: Option<Instant>,
+    /// Whether a background lookup is refreshing this entry.
+    refreshing: bool,
+}
+
+impl Entry {
+    /// Whether the entry has not expired yet at `now`.
+    fn is_fresh(&self, now: Instant) -> bool {
+        match self.expires {
+            Some(expires) => expires > now,
+            None => true,
+        }
+    }
+
+    /// Whether the entry can still be served at `now`, at most `stale_for`
+    /// after it expired.
+    fn is_usable(&self, now: Instant, stale_for: Duration) -> bool {
+        match self
+            .expires
+            .and_then(|expires| expires.checked_add(stale_for))
+        {
+            Some(usable_until) => usable_until > now,
+            None => true,
+        }
+    }
+}
+
+/// The result of looking up a name in the cache.
+enum Cached {
+    Fresh(SocketAddrs),
+    /// The entry expired, but may still be served while it is refreshed.
+    Stale(SocketAddrs),
+    Miss,
+}
+
+impl CachingTrustDnsResolver {
+    /// Create a new [`CachingTrustDnsResolver`] around `resolver` that holds
+    /// up to `capacity` entries for at most `max_ttl` each.
+    #[must_use]
+    pub fn new(resolver: TrustDnsResolver, max_ttl: Duration, capacity: usize) -> Self {
+        Self {
+            resolver,
+            cache: Arc::new(Cache::default()),
+            policy: CachePolicy {
+                max_ttl,
+                capacity,
+                stale_for: Duration::ZERO,
+            },
+        }
+    }
+
+    /// Keep serving entries for up to `stale_for` after they expired.
+    ///
+    /// A lookup that is answered with such a stale entry returns immediately
+    /// and refreshes the entry with a lookup in a background task. This must
+    /// be used inside a Tokio runtime context. Disabled by default.
+    #[must_use]
+    pub fn stale_while_revalidate(mut self, stale_for: Duration) -> Self {
+        self.policy.stale_for = stale_for;
+
+        self
+    }
+
+    /// Get the statistics of the cache.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cache lock was poisoned.
+    #[must_use]
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.cache.hits.load(Ordering::Relaxed),
+            stale_hits: self.cache.stale_hits.load(Ordering::Relaxed),
+            misses: self.cache.misses.load(Ordering::Relaxed),
+            size: self.cache.entries.lock().unwrap().len(),
+        }
+    }
+
+    /// Remove the cached entry of `name` from the cache of this resolver,
+    /// shared by all of its clones, so that the next lookup of `name` goes to
+    /// the resolver again. This does not clear the cache of the inner
+    /// [`TrustDnsResolver`], see [`TrustDnsResolver::clear_cache`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cache lock was poisoned.
+    pub fn clear_cache_for(&self, name: &str) {
+        self.cache.entries.lock().unwrap().remove(name);
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+
+    /// Refresh the stale entry of `name` in a background task, unless another
+    /// task already does.
+    fn refresh(&self, name: String) {
+        if !self.cache.start_refresh(&name) {
+            return;
+        }
+
+        let cache = self.cache.clone();
+        let policy = self.policy;
+        let lookup = self.resolver.resolve(name.clone());
+
+        tokio::spawn(async move {
+            match lookup.await {
+                Ok(addrs) => cache.insert(name, &addrs, policy),
+                Err(_) => cache.finish_refresh(&name),
+            }
+        });
+    }
+}
+
+impl Cache {
+    /// Get the addresses cached for `name`.
+    fn get(&self, name: &str, policy: CachePolicy) -> Cached {
+        let entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+
+        match entries.get(name) {
+            Some(entry) if entry.is_fresh(now) => Cached::Fresh(entry.addrs.clone()),
+            Some(entry) if entry.is_usable(now, policy.stale_for) => {
+                Cached::Stale(entry.addrs.clone())
+            }
+            _ => Cached::Miss,
+        }
+    }
+
+    /// Mark the entry of `name` as being refreshed. Returns `false` if it
+    /// already was.
+    fn start_refresh(&self, name: &str) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get_mut(name) {
+            Some(entry) if !entry.refreshing => {
+                entry.refreshing = true;
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Mark the entry of `name` as no longer being refreshed after the
+    /// refresh failed, so that the next lookup tries again.
+    fn finish_refresh(&self, name: &str) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(name) {
+            entry.refreshing = false;
+        }
+    }
+
+    /// Cache the addresses resolved for `name`, evicting entries if the cache
+    /// is full.
+    fn insert(&self, name: String, addrs: &SocketAddrs, policy: CachePolicy) {
+        if policy.capacity == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        let max_expires = now.checked_add(policy.max_ttl);
+        let expires = match (addrs.valid_until(), max_expires) {
+            (Some(valid_until), Some(max_expires)) => Some(valid_until.min(max_expires)),
+            (valid_until, max_expires) => valid_until.or(max_expires),
+        };
+
+        let mut entries = self.entries.lock().unwrap();
+
+        if !entries.contains_key(&name) && entries.len() >= policy.capacity {
+            entries.retain(|_, entry| entry.is_usable(now, policy.stale_for));
+
+            if entries.len() >= policy.capacity {
+                let soonest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| (entry.expires.is_none(), entry.expires))
+                    .map(|(name, _)| name.clone());
+
+                if let Some(soonest) = soonest {
+                    entries.remove(&soonest);
+                }
+            }
+        }
+
+        entries.insert(
+            name,
+            Entry {
+                addrs: addrs.clone(),
+                expires,
+                refreshing: false,
+            },
+        );
+    }
+}
+
+impl Resolve for CachingTrustDnsResolver {
+    type Future = <TrustDnsResolver as Service<Name>>::Future;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        let name = name.to_owned();
+
+        match self.cache.get(&name, self.policy) {
+            Cached::Fresh(addrs) => {
+                self.cache.hits.fetch_add(1, Ordering::Relaxed);
+
+                return Box::pin(async move { Ok(addrs) });
+            }
+            Cached::Stale(addrs) => {
+                self.cache.hits.fetch_add(1, Ordering::Relaxed);
+                self.cache.stale_hits.fetch_add(1, Ordering::Relaxed);
+
+                self.refresh(name);
+
+                return Box::pin(async move { Ok(addrs) });
+            }
+            Cached::Miss => {}
+        }
+
+        self.cache.misses.fetch_add(1, Ordering::Relaxed);
+
+        let cache = self.cache.clone();
+        let policy = self.policy;
+        let lookup = self.resolver.resolve(name.clone());
+
+        Box::pin(async move {
+            let addrs = lookup.await?;
+            cache.insert(name, &addrs, policy);
+
+            Ok(addrs)
+        })
+    }
+}
+
+impl_service!(CachingTrustDnsResolver);
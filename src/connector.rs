@@ -0,0 +1,411 @@
+//! Builders for connectors with non-default settings.
+
+use std::{net::IpAddr, time::Duration};
+
+#[cfg(feature = "native-tls")]
+use crate::NativeTlsHttpsConnector;
+#[cfg(any(feature = "rustls-native", feature = "rustls-webpki"))]
+use crate::RustlsHttpsConnector;
+use crate::{TrustDnsHttpConnector, TrustDnsResolver};
+
+/// Settings of a [`TrustDnsHttpConnector`], see
+/// [`TrustDnsResolver::into_http_connector_with_options`].
+///
+/// The defaults are the same as those of a new [`HttpConnector`], so
+/// individual settings can be changed with the struct update syntax:
+///
+/// ```
+/// # use hyper_trust_dns::HttpConnectorOptions;
+/// let options = HttpConnectorOptions {
+///     nodelay: true,
+///     ..HttpConnectorOptions::default()
+/// };
+/// ```
+///
+/// [`HttpConnector`]: hyper::client::HttpConnector
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct HttpConnectorOptions {
+    /// The timeout for establishing TCP connections. There is no timeout by
+    /// default.
+    pub connect_timeout: Option<Duration>,
+    /// How long to wait for a connection attempt to the preferred address
+    /// family before trying the other one, as described in RFC 6555.
+    /// Defaults to 300 milliseconds, `None` disables the fallback.
+    pub happy_eyeballs_timeout: Option<Duration>,
+    /// The idle time after which TCP keepalive probes are sent. Disabled by
+    /// default.
+    pub keepalive: Option<Duration>,
+    /// Whether to set the `TCP_NODELAY` option. Disabled by default.
+    pub nodelay: bool,
+    /// Whether to set the `SO_REUSEADDR` option. Disabled by default.
+    pub reuse_address: bool,
+    /// The size of the send buffer of sockets. Defaults to the setting of
+    /// the operating system.
+    pub send_buffer_size: Option<usize>,
+    /// The size of the receive buffer of sockets. Defaults to the setting of
+    /// the operating system.
+    pub recv_buffer_size: Option<usize>,
+    /// The local address connections are bound to.
+    pub local_address: Option<IpAddr>,
+    /// Whether to only accept URIs with the `http` scheme. Enabled by
+    /// default, disable it to wrap the connector in a TLS connector.
+    pub enforce_http: bool,
+}
+
+impl Default for HttpConnectorOptions {
+    fn default() -> Self {
+        Self {
+            connect_timeout: None,
+            happy_eyeballs_timeout: Some(Duration::from_millis(300)),
+            keepalive: None,
+            nodelay: false,
+            reuse_address: false,
+            send_buffer_size: None,
+            recv_buffer_size: None,
+            local_address: None,
+            enforce_http: true,
+        }
+    }
+}
+
+impl HttpConnectorOptions {
+    /// Create a new [`TrustDnsHttpConnector`] with these settings.
+    pub(crate) fn build(&self, resolver: TrustDnsResolver) -> TrustDnsHttpConnector {
+        let mut http_connector = resolver.into_http_connector();
+
+        http_connector.set_connect_timeout(self.connect_timeout);
+        http_connector.set_happy_eyeballs_timeout(self.happy_eyeballs_timeout);
+        http_connector.set_keepalive(self.keepalive);
+        http_connector.set_nodelay(self.nodelay);
+        http_connector.set_reuse_address(self.reuse_address);
+        http_connector.set_send_buffer_size(self.send_buffer_size);
+        http_connector.set_recv_buffer_size(self.recv_buffer_size);
+        http_connector.set_local_address(self.local_address);
+        http_connector.enforce_http(self.enforce_http);
+
+        http_connector
+    }
+}
+
+/// A builder for a [`TrustDnsHttpConnector`] with custom settings.
+#[must_use]
+pub struct TrustDnsHttpConnectorBuilder {
+    resolver: TrustDnsResolver,
+    http: HttpConnectorOptions,
+}
+
+impl TrustDnsHttpConnectorBuilder {
+    /// Create a new [`TrustDnsHttpConnectorBuilder`] with `resolver`.
+    pub fn new(resolver: TrustDnsResolver) -> Self {
+        Self {
+            resolver,
+            http: HttpConnectorOptions::default(),
+        }
+    }
+
+    /// Set the timeout for establishing TCP connections. There is no timeout
+    /// by default.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.http.connect_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Set the `TCP_NODELAY` option on connections. Disabled by default.
+    pub fn nodelay(mut self, enabled: bool) -> Self {
+        self.http.nodelay = enabled;
+
+        self
+    }
+
+    /// Send TCP keepalive probes after connections have been idle for
+    /// `interval`. Disabled by default.
+    pub fn keepalive(mut self, interval: Duration) -> Self {
+        self.http.keepalive = Some(interval);
+
+        self
+    }
+
+    /// Bind connections to the local address `addr`.
+    pub fn local_address(mut self, addr: IpAddr) -> Self {
+        self.http.local_address = Some(addr);
+
+        self
+    }
+
+    /// Only accept URIs with the `http` scheme. Enabled by default, disable it
+    /// to wrap the connector in a TLS connector.
+    pub fn enforce_http(mut self, enabled: bool) -> Self {
+        self.http.enforce_http = enabled;
+
+        self
+    }
+
+    /// Build the [`TrustDnsHttpConnector`].
+    #[must_use]
+    pub fn build(self) -> TrustDnsHttpConnector {
+        self.http.build(self.resolver)
+    }
+}
+
+/// A builder for a [`NativeTlsHttpsConnector`] with custom settings.
+#[cfg(feature = "native-tls")]
+#[must_use]
+pub struct NativeTlsHttpsConnectorBuilder {
+    resolver: TrustDnsResolver,
+    http: HttpConnectorOptions,
+    tls: Option<tokio_native_tls::native_tls::TlsConnector>,
+    https_only: bool,
+}
+
+#[cfg(feature = "native-tls")]
+impl NativeTlsHttpsConnectorBuilder {
+    /// Create a new [`NativeTlsHttpsConnectorBuilder`] with `resolver`.
+    pub fn new(resolver: TrustDnsResolver) -> Self {
+        Self {
+            resolver,
+            http: HttpConnectorOptions::default(),
+            tls: None,
+            https_only: cfg!(feature = "https-only"),
+        }
+    }
+
+    /// Only allow HTTPS connections. Defaults to whether the `https-only`
+    /// feature is enabled.
+    pub fn https_only(mut self, enabled: bool) -> Self {
+        self.https_only = enabled;
+
+        self
+    }
+
+    /// Set the timeout for establishing TCP connections. There is no timeout
+    /// by default.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.http.connect_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Set the `TCP_NODELAY` option on connections. Disabled by default.
+    pub fn nodelay(mut self, enabled: bool) -> Self {
+        self.http.nodelay = enabled;
+
+        self
+    }
+
+    /// Bind connections to the local address `addr`.
+    pub fn local_address(mut self, addr: IpAddr) -> Self {
+        self.http.local_address = Some(addr);
+
+        self
+    }
+
+    /// Use a custom [`TlsConnector`](tokio_native_tls::native_tls::TlsConnector)
+    /// instead of the default one.
+    pub fn tls_connector(mut self, tls: tokio_native_tls::native_tls::TlsConnector) -> Self {
+        self.tls = Some(tls);
+
+        self
+    }
+
+    /// Build the [`NativeTlsHttpsConnector`].
+    #[must_use]
+    pub fn build(self) -> NativeTlsHttpsConnector {
+        let mut http_connector = self.http.build(self.resolver);
+        http_connector.enforce_http(false);
+
+        let mut native_https_connector = match self.tls {
+            Some(tls) => NativeTlsHttpsConnector::from((http_connector, tls.into())),
+            None => NativeTlsHttpsConnector::new_with_connector(http_connector),
+        };
+
+        native_https_connector.https_only(self.https_only);
+
+        native_https_connector
+    }
+}
+
+/// The root certificates used by a [`RustlsHttpsConnectorBuilder`].
+#[cfg(any(feature = "rustls-native", feature = "rustls-webpki"))]
+enum Roots {
+    #[cfg(feature = "rustls-native")]
+    Native,
+    #[cfg(feature = "rustls-webpki")]
+    Webpki,
+    Custom(Vec<rustls::Certificate>),
+}
+
+/// A builder for a [`RustlsHttpsConnector`] with custom settings.
+///
+/// By default, the connector uses the `webpki_roots` if the `rustls-webpki`
+/// feature is enabled and the OS root store otherwise.
+#[cfg(all(
+    any(feature = "rustls-native", feature = "rustls-webpki"),
+    any(feature = "rustls-http1", feature = "rustls-http2")
+))]
+#[must_use]
+pub struct RustlsHttpsConnectorBuilder {
+    resolver: TrustDnsResolver,
+    http: HttpConnectorOptions,
+    roots: Roots,
+    https_only: bool,
+    #[cfg(all(feature = "rustls-http1", feature = "rustls-http2"))]
+    http1: bool,
+    #[cfg(all(feature = "rustls-http1", feature = "rustls-http2"))]
+    http2: bool,
+}
+
+#[cfg(all(
+    any(feature = "rustls-native", feature = "rustls-webpki"),
+    any(feature = "rustls-http1", feature = "rustls-http2")
+))]
+impl RustlsHttpsConnectorBuilder {
+    /// Create a new [`RustlsHttpsConnectorBuilder`] with `resolver`.
+    pub fn new(resolver: TrustDnsResolver) -> Self {
+        Self {
+            resolver,
+            http: HttpConnectorOptions::default(),
+            #[cfg(feature = "rustls-webpki")]
+            roots: Roots::Webpki,
+            #[cfg(not(feature = "rustls-webpki"))]
+            roots: Roots::Native,
+            https_only: cfg!(feature = "https-only"),
+            #[cfg(all(feature = "rustls-http1", feature = "rustls-http2"))]
+            http1: true,
+            #[cfg(all(feature = "rustls-http1", feature = "rustls-http2"))]
+            http2: true,
+        }
+    }
+
+    /// Use the OS root store.
+    #[cfg(feature = "rustls-native")]
+    pub fn native_roots(mut self) -> Self {
+        self.roots = Roots::Native;
+
+        self
+    }
+
+    /// Use the `webpki_roots`.
+    #[cfg(feature = "rustls-webpki")]
+    pub fn webpki_roots(mut self) -> Self {
+        self.roots = Roots::Webpki;
+
+        self
+    }
+
+    /// Only trust the DER-encoded root certificates `roots`.
+    ///
+    /// Certificates that cannot be parsed are silently skipped, so the
+    /// connector rejects every server if none of `roots` can be parsed. Use
+    /// [`TrustDnsResolver::into_rustls_https_connector_with_roots`] with a
+    /// [`RootCertStore`](rustls::RootCertStore) built with
+    /// [`RootCertStore::add`](rustls::RootCertStore::add) to get an error for
+    /// invalid certificates instead.
+    pub fn custom_roots(mut self, roots: Vec<rustls::Certificate>) -> Self {
+        self.roots = Roots::Custom(roots);
+
+        self
+    }
+
+    /// Only allow HTTPS connections. Defaults to whether the `https-only`
+    /// feature is enabled.
+    pub fn https_only(mut self, enabled: bool) -> Self {
+        self.https_only = enabled;
+
+        self
+    }
+
+    /// Offer HTTP/1.1 via ALPN. Enabled by default.
+    #[cfg(all(feature = "rustls-http1", feature = "rustls-http2"))]
+    pub fn http1(mut self, enabled: bool) -> Self {
+        self.http1 = enabled;
+
+        self
+    }
+
+    /// Offer HTTP/2 via ALPN. Enabled by default.
+    #[cfg(all(feature = "rustls-http1", feature = "rustls-http2"))]
+    pub fn http2(mut self, enabled: bool) -> Self {
+        self.http2 = enabled;
+
+        self
+    }
+
+    /// Set the timeout for establishing TCP connections. There is no timeout
+    /// by default.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.http.connect_timeout = Some(timeout);
+
+        self
+    }
+
+    /// Set the `TCP_NODELAY` option on connections. Disabled by default.
+    pub fn nodelay(mut self, enabled: bool) -> Self {
+        self.http.nodelay = enabled;
+
+        self
+    }
+
+    /// Bind connections to the local address `addr`.
+    pub fn local_address(mut self, addr: IpAddr) -> Self {
+        self.http.local_address = Some(addr);
+
+        self
+    }
+
+    /// Build the [`RustlsHttpsConnector`].
+    ///
+    /// If both HTTP/1.1 and HTTP/2 were disabled, HTTP/1.1 is used.
+    #[must_use]
+    pub fn build(self) -> RustlsHttpsConnector {
+        let mut http_connector = self.http.build(self.resolver);
+        http_connector.enforce_http(false);
+
+        let builder = hyper_rustls::HttpsConnectorBuilder::new();
+
+        let builder = match self.roots {
+            #[cfg(feature = "rustls-native")]
+            Roots::Native => builder.with_native_roots(),
+            #[cfg(feature = "rustls-webpki")]
+            Roots::Webpki => builder.with_webpki_roots(),
+            Roots::Custom(certs) => {
+                let certs: Vec<Vec<u8>> = certs.into_iter().map(|cert| cert.0).collect();
+
+                let mut root_store = rustls::RootCertStore::empty();
+                root_store.add_parsable_certificates(&certs);
+
+                let tls_config = rustls::ClientConfig::builder()
+                    .with_safe_defaults()
+                    .with_root_certificates(root_store)
+                    .with_no_client_auth();
+
+                builder.with_tls_config(tls_config)
+            }
+        };
+
+        let builder = if self.https_only {
+            builder.https_only()
+        } else {
+            builder.https_or_http()
+        };
+
+        #[cfg(all(feature = "rustls-http1", feature = "rustls-http2"))]
+        let connector = match (self.http1, self.http2) {
+            (true, true) => builder
+                .enable_http1()
+                .enable_http2()
+                .wrap_connector(http_connector),
+            (false, true) => builder.enable_http2().wrap_connector(http_connector),
+            (_, false) => builder.enable_http1().wrap_connector(http_connector),
+        };
+
+        #[cfg(not(feature = "rustls-http2"))]
+        let connector = builder.enable_http1().wrap_connector(http_connector);
+
+        #[cfg(not(feature = "rustls-http1"))]
+        let connector = builder.enable_http2().wrap_connector(http_connector);
+
+        connector
+    }
+}
@@ -0,0 +1,298 @@
+//! Lookups with the EDNS Client Subnet option (RFC 7871).
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use ipnet::IpNet;
+
+use crate::{
+    resolver::{
+        config::{LookupIpStrategy, Protocol, ResolverConfig},
+        error::ResolveErrorKind,
+        proto::{
+            iocompat::AsyncIoTokioAsStd,
+            op::{Edns, Message, NoopMessageFinalizer, Query, ResponseCode},
+            rr::{
+                rdata::opt::{ClientSubnet, EdnsOption},
+                Name, RData, RecordType,
+            },
+            tcp::TcpClientStream,
+            udp::UdpClientStream,
+            xfer::{
+                DnsExchange, DnsHandle, DnsMultiplexer, DnsRequest, DnsRequestOptions, DnsResponse,
+                FirstAnswer,
+            },
+            TokioTime,
+        },
+    },
+    ResolveError,
+};
+
+/// The EDNS payload size recommended by the DNS flag day 2020.
+const MAX_PAYLOAD: u16 = 1232;
+
+/// A nameserver of the config that can be queried without encryption.
+#[derive(Debug, Clone, Copy)]
+struct PlainNameServer {
+    addr: SocketAddr,
+    udp: bool,
+    tcp: bool,
+}
+
+/// Collect the nameservers of a [`ResolverConfig`] that are queried via
+/// plain UDP or TCP. Encrypted nameservers are skipped, sending them
+/// unencrypted queries would either fail or disclose the queries.
+fn plain_name_servers(config: &ResolverConfig) -> Vec<PlainNameServer> {
+    let mut name_servers: Vec<PlainNameServer> = Vec::new();
+
+    for name_server in config.name_servers() {
+        let (udp, tcp) = match name_server.protocol {
+            Protocol::Udp => (true, false),
+            Protocol::Tcp => (false, true),
+            _ => continue,
+        };
+
+        match name_servers
+            .iter_mut()
+            .find(|other| other.addr == name_server.socket_addr)
+        {
+            Some(other) => {
+                other.udp |= udp;
+                other.tcp |= tcp;
+            }
+            None => name_servers.push(PlainNameServer {
+                addr: name_server.socket_addr,
+                udp,
+                tcp,
+            }),
+        }
+    }
+
+    name_servers
+}
+
+/// Returns `true` if the [`ResolverConfig`] has nameservers that lookups with
+/// the EDNS Client Subnet option can be sent to.
+pub(crate) fn has_plain_name_servers(config: &ResolverConfig) -> bool {
+    !plain_name_servers(config).is_empty()
+}
+
+/// Sends the queries of lookups directly to the nameservers, with the EDNS
+/// Client Subnet option that the resolver backend cannot add itself.
+///
+/// Only the nameservers that are queried via plain UDP or TCP are used. UDP
+/// queries with truncated responses are retried via TCP if the nameserver is
+/// also configured for TCP. These lookups bypass the cache, the hosts file
+/// and the search domains of the resolver.
+#[derive(Debug)]
+pub(crate) struct ClientSubnetLookup {
+    subnet: IpNet,
+    name_servers: Vec<PlainNameServer>,
+    record_types: &'static [RecordType],
+    timeout: Duration,
+}
+
+impl ClientSubnetLookup {
+    pub(crate) fn new(
+        subnet: IpNet,
+        config: &ResolverConfig,
+        ip_strategy: LookupIpStrategy,
+        timeout: Duration,
+    ) -> Self {
+        let record_types: &'static [RecordType] = match ip_strategy {
+            LookupIpStrategy::Ipv4Only => &[RecordType::A],
+            LookupIpStrategy::Ipv6Only => &[RecordType::AAAA],
+            _ => &[RecordType::AAAA, RecordType::A],
+        };
+
+        Self {
+            subnet: subnet.trunc(),
+            name_servers: plain_name_servers(config),
+            record_types,
+            timeout,
+        }
+    }
+
+    /// Look up the addresses of `name`, trying the nameservers in order.
+    /// Alongside the addresses, this returns when the records expire.
+    pub(crate) async fn lookup(
+        &self,
+        name: &str,
+    ) -> Result<(Vec<IpAddr>, Option<Instant>), ResolveError> {
+        if let Ok(ip) = name.parse::<IpAddr>() {
+            return Ok((vec![ip], None));
+        }
+
+        let mut name = Name::from_str(name)?;
+        name.set_fqdn(true);
+
+        let mut result = Err(ResolveError::from("no unencrypted nameservers to query"));
+
+        for &name_server in &self.name_servers {
+            result = self.lookup_with(name_server, &name).await;
+
+            if result.is_ok() {
+                break;
+            }
+        }
+
+        result
+    }
+
+    /// Look up the addresses of `name` with a single nameserver. The
+    /// addresses of the record types that were found are returned even if
+    /// the queries for the others failed.
+    async fn lookup_with(
+        &self,
+        name_server: PlainNameServer,
+        name: &Name,
+    ) -> Result<(Vec<IpAddr>, Option<Instant>), ResolveError> {
+        let mut ips = Vec::new();
+        let mut min_ttl: Option<u32> = None;
+        let mut first_error = None;
+
+        for &record_type in self.record_types {
+            let response = match self.query(name_server, name, record_type).await {
+                Ok(response) => response,
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                    continue;
+                }
+            };
+
+            for record in response.answers() {
+                let ip = match record.data() {
+                    Some(RData::A(a)) => IpAddr::V4(a.0),
+                    Some(RData::AAAA(aaaa)) => IpAddr::V6(aaaa.0),
+                    _ => continue,
+                };
+
+                ips.push(ip);
+                min_ttl = Some(min_ttl.map_or(record.ttl(), |ttl| ttl.min(record.ttl())));
+            }
+        }
+
+        if ips.is_empty() {
+            return Err(first_error
+                .unwrap_or_else(|| no_records(name, self.record_types[0], ResponseCode::NoError)));
+        }
+
+        let valid_until = min_ttl.map(|ttl| Instant::now() + Duration::from_secs(u64::from(ttl)));
+
+        Ok((ips, valid_until))
+    }
+
+    /// Query a single nameserver for `record_type` records of `name`,
+    /// preferring UDP and retrying truncated responses via TCP.
+    async fn query(
+        &self,
+        name_server: PlainNameServer,
+        name: &Name,
+        record_type: RecordType,
+    ) -> Result<DnsResponse, ResolveError> {
+        let message = self.message(name, record_type);
+
+        let response = if name_server.udp {
+            let response = self.send_udp(name_server.addr, message.clone()).await?;
+
+            if !response.truncated() {
+                response
+            } else if name_server.tcp {
+                self.send_tcp(name_server.addr, message).await?
+            } else {
+                return Err(ResolveError::from(format!(
+                    "truncated response from {}, which is not configured for TCP",
+                    name_server.addr
+                )));
+            }
+        } else {
+            self.send_tcp(name_server.addr, message).await?
+        };
+
+        match response.response_code() {
+            ResponseCode::NoError => Ok(response),
+            ResponseCode::NXDomain => Err(no_records(name, record_type, ResponseCode::NXDomain)),
+            code => Err(ResolveError::from(format!(
+                "{} answered the query for {name} with {code}",
+                name_server.addr
+            ))),
+        }
+    }
+
+    async fn send_udp(
+        &self,
+        addr: SocketAddr,
+        message: Message,
+    ) -> Result<DnsResponse, ResolveError> {
+        let stream = UdpClientStream::<tokio::net::UdpSocket>::with_timeout(addr, self.timeout);
+        let (client, background) = DnsExchange::connect::<_, _, TokioTime>(stream).await?;
+        tokio::spawn(background);
+
+        send(client, message).await
+    }
+
+    async fn send_tcp(
+        &self,
+        addr: SocketAddr,
+        message: Message,
+    ) -> Result<DnsResponse, ResolveError> {
+        let (stream, handle) =
+            TcpClientStream::<AsyncIoTokioAsStd<tokio::net::TcpStream>>::with_timeout(
+                addr,
+                self.timeout,
+            );
+        let multiplexer = DnsMultiplexer::<_, NoopMessageFinalizer>::with_timeout(
+            stream,
+            handle,
+            self.timeout,
+            None,
+        );
+        let (client, background) = DnsExchange::connect::<_, _, TokioTime>(multiplexer).await?;
+        tokio::spawn(background);
+
+        send(client, message).await
+    }
+
+    /// Create the query for `record_type` records of `name`.
+    fn message(&self, name: &Name, record_type: RecordType) -> Message {
+        let mut edns = Edns::new();
+        edns.set_max_payload(MAX_PAYLOAD);
+        edns.options_mut()
+            .insert(EdnsOption::Subnet(ClientSubnet::new(
+                self.subnet.addr(),
+                self.subnet.prefix_len(),
+                0,
+            )));
+
+        let mut message = Message::new();
+        message
+            .set_recursion_desired(true)
+            .add_query(Query::query(name.clone(), record_type))
+            .set_edns(edns);
+
+        message
+    }
+}
+
+/// Send `message` through `client` and wait for the response.
+async fn send(client: DnsExchange, message: Message) -> Result<DnsResponse, ResolveError> {
+    let request = DnsRequest::new(message, DnsRequestOptions::default());
+
+    // The backends differ in whether sending takes `&self` or `&mut self`
+    Ok(client.clone().send(request).first_answer().await?)
+}
+
+/// The error for a lookup of `name` that returned no addresses.
+fn no_records(name: &Name, record_type: RecordType, response_code: ResponseCode) -> ResolveError {
+    ResolveErrorKind::NoRecordsFound {
+        query: Box::new(Query::query(name.clone(), record_type)),
+        soa: None,
+        negative_ttl: None,
+        response_code,
+        trusted: false,
+    }
+    .into()
+}
@@ -0,0 +1,132 @@
+//! Resolver configurations for custom DNS-over-TLS and DNS-over-HTTPS servers.
+
+use std::net::IpAddr;
+#[cfg(feature = "dns-over-https-rustls")]
+use std::{error::Error, fmt, io, net::ToSocketAddrs};
+
+use crate::resolver::config::{NameServerConfigGroup, ResolverConfig};
+
+/// The only path the resolver backend sends DNS-over-HTTPS requests to.
+#[cfg(feature = "dns-over-https-rustls")]
+const DOH_PATH: &str = "/dns-query";
+
+/// Create a [`ResolverConfig`] for the DNS-over-TLS server at `ip` and
+/// `port` with the TLS name `tls_name`.
+#[cfg(any(
+    feature = "dns-over-rustls",
+    feature = "dns-over-native-tls",
+    feature = "dns-over-openssl"
+))]
+pub(crate) fn dot_config(ip: IpAddr, port: u16, tls_name: &str) -> ResolverConfig {
+    let name_servers = NameServerConfigGroup::from_ips_tls(&[ip], port, tls_name.to_owned(), true);
+
+    ResolverConfig::from_parts(None, vec![], name_servers)
+}
+
+/// Create a [`ResolverConfig`] for the DNS-over-HTTPS server at `url`.
+#[cfg(feature = "dns-over-https-rustls")]
+pub(crate) fn doh_config(url: &str) -> Result<ResolverConfig, DohEndpointError> {
+    let rest = url
+        .strip_prefix("https://")
+        .ok_or(DohEndpointError::InvalidUrl)?;
+    let (authority, path) = rest
+        .find('/')
+        .map_or((rest, DOH_PATH), |i| rest.split_at(i));
+
+    if path != DOH_PATH {
+        return Err(DohEndpointError::UnsupportedPath);
+    }
+
+    let (host, port) = split_host_port(authority, 443)?;
+
+    let ips: Vec<IpAddr> = match host.parse() {
+        Ok(ip) => vec![ip],
+        Err(_) => (host, port)
+            .to_socket_addrs()
+            .map_err(DohEndpointError::Resolve)?
+            .map(|addr| addr.ip())
+            .collect(),
+    };
+
+    if ips.is_empty() {
+        return Err(DohEndpointError::Resolve(io::Error::new(
+            io::ErrorKind::NotFound,
+            "host has no addresses",
+        )));
+    }
+
+    let name_servers = NameServerConfigGroup::from_ips_https(&ips, port, host.to_owned(), true);
+
+    Ok(ResolverConfig::from_parts(None, vec![], name_servers))
+}
+
+/// Split the authority of a URL into host and port, falling back to
+/// `default_port` if there is none.
+#[cfg(feature = "dns-over-https-rustls")]
+fn split_host_port(authority: &str, default_port: u16) -> Result<(&str, u16), DohEndpointError> {
+    // IPv6 addresses are enclosed in brackets
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']').ok_or(DohEndpointError::InvalidUrl)?;
+
+        match rest {
+            "" => (host, None),
+            _ => (
+                host,
+                Some(rest.strip_prefix(':').ok_or(DohEndpointError::InvalidUrl)?),
+            ),
+        }
+    } else {
+        match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+
+    if host.is_empty() || host.contains('@') {
+        return Err(DohEndpointError::InvalidUrl);
+    }
+
+    let port = match port {
+        Some(port) => port.parse().map_err(|_| DohEndpointError::InvalidUrl)?,
+        None => default_port,
+    };
+
+    Ok((host, port))
+}
+
+/// An error returned when a DNS-over-HTTPS endpoint cannot be used.
+#[cfg(feature = "dns-over-https-rustls")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DohEndpointError {
+    /// The endpoint is not a valid `https://` URL.
+    InvalidUrl,
+    /// The endpoint has a path other than `/dns-query`, which is the only
+    /// path supported by the resolver.
+    UnsupportedPath,
+    /// The host of the endpoint could not be resolved.
+    Resolve(io::Error),
+}
+
+#[cfg(feature = "dns-over-https-rustls")]
+impl fmt::Display for DohEndpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUrl => f.write_str("invalid DNS-over-HTTPS endpoint URL"),
+            Self::UnsupportedPath => {
+                write!(f, "DNS-over-HTTPS endpoints must use the path {DOH_PATH}")
+            }
+            Self::Resolve(_) => f.write_str("failed to resolve DNS-over-HTTPS endpoint host"),
+        }
+    }
+}
+
+#[cfg(feature = "dns-over-https-rustls")]
+impl Error for DohEndpointError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Resolve(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,71 @@
+//! Reading the resolver configuration from environment variables.
+
+use std::{env, error::Error, fmt};
+
+use crate::{
+    name_servers::{self, NameServerError},
+    resolver::config::ResolverConfig,
+};
+
+/// The comma-separated nameservers to use.
+const NAMESERVER_VAR: &str = "TRUST_DNS_NAMESERVER";
+/// The protocol used to query the nameservers.
+const PROTOCOL_VAR: &str = "TRUST_DNS_PROTOCOL";
+/// The TLS name of the nameservers for DNS-over-TLS and DNS-over-HTTPS.
+const TLS_NAME_VAR: &str = "TRUST_DNS_TLS_NAME";
+
+/// An error returned when the resolver configuration in the environment is
+/// invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EnvConfigError {
+    /// `TRUST_DNS_NAMESERVER` is not set or empty.
+    MissingNameserver,
+    /// An entry of `TRUST_DNS_NAMESERVER` is not an IP address with an
+    /// optional port.
+    InvalidNameserver(String),
+    /// `TRUST_DNS_PROTOCOL` is not a known protocol, or the feature for it is
+    /// not enabled.
+    UnsupportedProtocol(String),
+    /// `TRUST_DNS_TLS_NAME` is not set, but the protocol requires it.
+    MissingTlsName,
+}
+
+impl fmt::Display for EnvConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingNameserver => write!(f, "{NAMESERVER_VAR} is not set"),
+            Self::InvalidNameserver(server) => {
+                write!(f, "invalid nameserver {server:?} in {NAMESERVER_VAR}")
+            }
+            Self::UnsupportedProtocol(protocol) => {
+                write!(f, "unsupported protocol {protocol:?} in {PROTOCOL_VAR}")
+            }
+            Self::MissingTlsName => write!(f, "{TLS_NAME_VAR} is required for this protocol"),
+        }
+    }
+}
+
+impl Error for EnvConfigError {}
+
+impl From<NameServerError> for EnvConfigError {
+    fn from(error: NameServerError) -> Self {
+        match error {
+            NameServerError::Missing => Self::MissingNameserver,
+            NameServerError::Invalid(server) => Self::InvalidNameserver(server),
+            NameServerError::UnsupportedProtocol(protocol) => Self::UnsupportedProtocol(protocol),
+            NameServerError::MissingTlsName => Self::MissingTlsName,
+        }
+    }
+}
+
+/// Create a [`ResolverConfig`] from the environment variables.
+pub(crate) fn config_from_env() -> Result<ResolverConfig, EnvConfigError> {
+    let servers = env::var(NAMESERVER_VAR).unwrap_or_default();
+    let protocol = env::var(PROTOCOL_VAR).unwrap_or_else(|_| String::from("udp"));
+    let tls_name = env::var(TLS_NAME_VAR).ok().filter(|name| !name.is_empty());
+
+    let name_servers = name_servers::parse(servers.split(','), &protocol, tls_name.as_deref())?;
+
+    Ok(ResolverConfig::from_parts(None, vec![], name_servers))
+}
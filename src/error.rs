@@ -0,0 +1,85 @@
+//! Converting resolver errors.
+
+use std::{error::Error, fmt, io};
+
+use crate::{resolver::error::ResolveErrorKind, ResolveError};
+
+/// The error returned by the resolver services when a lookup fails.
+///
+/// [`ResolveError`] includes the message of the I/O or protocol error that
+/// caused it, but does not return that error from [`Error::source`], so
+/// error reporters that walk the source chain like `anyhow` stop at the
+/// resolver. This wraps the [`ResolveError`] and returns its cause as the
+/// source.
+#[derive(Debug, Clone)]
+pub struct DnsConnectError(ResolveError);
+
+impl DnsConnectError {
+    /// Get the kind of the wrapped [`ResolveError`].
+    #[must_use]
+    pub fn kind(&self) -> &ResolveErrorKind {
+        self.0.kind()
+    }
+
+    /// Get the wrapped [`ResolveError`].
+    #[must_use]
+    pub fn into_inner(self) -> ResolveError {
+        self.0
+    }
+}
+
+impl fmt::Display for DnsConnectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Error for DnsConnectError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self.0.kind() {
+            ResolveErrorKind::Io(e) => Some(e),
+            ResolveErrorKind::Proto(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ResolveError> for DnsConnectError {
+    fn from(e: ResolveError) -> Self {
+        Self(e)
+    }
+}
+
+impl From<DnsConnectError> for ResolveError {
+    fn from(e: DnsConnectError) -> Self {
+        e.0
+    }
+}
+
+impl From<DnsConnectError> for io::Error {
+    fn from(e: DnsConnectError) -> Self {
+        resolve_error_to_io(e.0)
+    }
+}
+
+/// Convert a [`ResolveError`] into an [`io::Error`], for example in custom
+/// connectors that report errors as [`io::Error`] like hyper's.
+///
+/// Unlike the [`From`] implementation of the resolver backend, which only
+/// distinguishes timeouts, this maps the error to the closest
+/// [`io::ErrorKind`]: failed I/O keeps its kind, names without addresses are
+/// [`io::ErrorKind::NotFound`] and the lack of usable nameservers is
+/// [`io::ErrorKind::NotConnected`]. The original error is kept as the inner
+/// error.
+#[must_use]
+pub fn resolve_error_to_io(e: ResolveError) -> io::Error {
+    let kind = match e.kind() {
+        ResolveErrorKind::Io(e) => e.kind(),
+        ResolveErrorKind::NoRecordsFound { .. } => io::ErrorKind::NotFound,
+        ResolveErrorKind::NoConnections => io::ErrorKind::NotConnected,
+        ResolveErrorKind::Timeout => io::ErrorKind::TimedOut,
+        _ => io::ErrorKind::Other,
+    };
+
+    io::Error::new(kind, e)
+}
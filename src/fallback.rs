@@ -0,0 +1,65 @@
+//! Trying several resolvers in order.
+
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+
+use crate::{
+    service::{impl_service, Resolve},
+    ResolveError, TrustDnsResolver,
+};
+
+/// A resolver that tries a list of [`TrustDnsResolver`]s in order.
+///
+/// Each lookup goes to the first resolver and only moves on to the next one
+/// if the lookup failed or returned no addresses. The result of the last
+/// resolver is returned as it is. Unlike a [`SplitDnsTrustDnsResolver`], the
+/// name is not taken into account.
+///
+/// [`SplitDnsTrustDnsResolver`]: crate::SplitDnsTrustDnsResolver
+#[derive(Debug, Clone)]
+pub struct FallbackTrustDnsResolver {
+    resolvers: Vec<TrustDnsResolver>,
+}
+
+impl FallbackTrustDnsResolver {
+    /// Create a new [`FallbackTrustDnsResolver`] that tries `resolvers` in
+    /// the given order. Lookups fail if the list is empty.
+    #[must_use]
+    pub fn new(resolvers: Vec<TrustDnsResolver>) -> Self {
+        Self { resolvers }
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+}
+
+impl Resolve for FallbackTrustDnsResolver {
+    type Future = <TrustDnsResolver as Service<Name>>::Future;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        let name = name.to_owned();
+
+        let resolvers = self.resolvers.clone();
+
+        Box::pin(async move {
+            let mut result = Err(ResolveError::from("no resolvers to fall back to").into());
+
+            for resolver in &resolvers {
+                result = resolver.resolve(name.clone()).await;
+
+                if matches!(&result, Ok(addrs) if !addrs.is_empty()) {
+                    break;
+                }
+            }
+
+            result
+        })
+    }
+}
+
+impl_service!(FallbackTrustDnsResolver);
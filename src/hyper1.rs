@@ -0,0 +1,120 @@
+//! Connectors for hyper 1.x.
+//!
+//! hyper 1.x no longer ships a client connector itself, so the types in this
+//! module are built on top of the legacy client in [`hyper_util`]. They mirror
+//! the connectors in the crate root, which target hyper 0.14.
+
+use std::task::{self, Poll};
+
+use hyper::service::Service;
+use hyper_util::client::legacy::connect::{dns::Name, HttpConnector};
+
+use crate::{DnsConnectError, SocketAddrs, TrustDnsResolver};
+
+/// A hyper 1.x [`HttpConnector`] that uses the [`TrustDnsResolver`].
+pub type TrustDnsHttpConnector = HttpConnector<TrustDnsResolver>;
+
+/// A [`hyper1_tls::HttpsConnector`] that uses a hyper 1.x [`TrustDnsHttpConnector`].
+#[cfg(feature = "hyper1-native-tls")]
+pub type NativeTlsHttpsConnector = hyper1_tls::HttpsConnector<TrustDnsHttpConnector>;
+
+/// A [`hyper1_rustls::HttpsConnector`] that uses a hyper 1.x [`TrustDnsHttpConnector`].
+#[cfg(all(
+    feature = "hyper1-rustls",
+    any(feature = "rustls-native", feature = "rustls-webpki")
+))]
+pub type RustlsHttpsConnector = hyper1_rustls::HttpsConnector<TrustDnsHttpConnector>;
+
+impl TrustDnsResolver {
+    /// Create a new hyper 1.x [`TrustDnsHttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_hyper1_http_connector(self) -> TrustDnsHttpConnector {
+        TrustDnsHttpConnector::new_with_resolver(self)
+    }
+
+    /// Create a new hyper 1.x [`NativeTlsHttpsConnector`].
+    #[cfg(feature = "hyper1-native-tls")]
+    #[must_use]
+    pub fn into_hyper1_native_tls_https_connector(self) -> NativeTlsHttpsConnector {
+        let mut http_connector = self.into_hyper1_http_connector();
+        http_connector.enforce_http(false);
+
+        let mut native_https_connector =
+            NativeTlsHttpsConnector::new_with_connector(http_connector);
+
+        #[cfg(feature = "https-only")]
+        native_https_connector.https_only(true);
+
+        #[cfg(not(feature = "https-only"))]
+        native_https_connector.https_only(false);
+
+        native_https_connector
+    }
+
+    /// Create a new hyper 1.x [`RustlsHttpsConnector`] using the OS root store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the OS root store could not be loaded.
+    #[cfg(all(feature = "hyper1-rustls", feature = "rustls-native"))]
+    pub fn into_hyper1_rustls_native_https_connector(
+        self,
+    ) -> std::io::Result<RustlsHttpsConnector> {
+        let mut http_connector = self.into_hyper1_http_connector();
+        http_connector.enforce_http(false);
+
+        let builder = hyper1_rustls::HttpsConnectorBuilder::new().with_native_roots()?;
+
+        #[cfg(feature = "https-only")]
+        let builder = builder.https_only();
+
+        #[cfg(not(feature = "https-only"))]
+        let builder = builder.https_or_http();
+
+        #[cfg(feature = "rustls-http1")]
+        let builder = builder.enable_http1();
+
+        #[cfg(feature = "rustls-http2")]
+        let builder = builder.enable_http2();
+
+        Ok(builder.wrap_connector(http_connector))
+    }
+
+    /// Create a new hyper 1.x [`RustlsHttpsConnector`] using the `webpki_roots`.
+    #[cfg(all(feature = "hyper1-rustls", feature = "rustls-webpki"))]
+    #[must_use]
+    pub fn into_hyper1_rustls_webpki_https_connector(self) -> RustlsHttpsConnector {
+        let mut http_connector = self.into_hyper1_http_connector();
+        http_connector.enforce_http(false);
+
+        let builder = hyper1_rustls::HttpsConnectorBuilder::new().with_webpki_roots();
+
+        #[cfg(feature = "https-only")]
+        let builder = builder.https_only();
+
+        #[cfg(not(feature = "https-only"))]
+        let builder = builder.https_or_http();
+
+        #[cfg(feature = "rustls-http1")]
+        let builder = builder.enable_http1();
+
+        #[cfg(feature = "rustls-http2")]
+        let builder = builder.enable_http2();
+
+        builder.wrap_connector(http_connector)
+    }
+}
+
+impl Service<Name> for TrustDnsResolver {
+    type Response = SocketAddrs;
+    type Error = DnsConnectError;
+    type Future = <Self as Service<hyper::client::connect::dns::Name>>::Future;
+
+    fn poll_ready(&mut self, _cx: &mut task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        self.resolve(name.as_str().to_owned())
+    }
+}
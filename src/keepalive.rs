@@ -0,0 +1,74 @@
+//! TCP keepalive probes for connections of a [`TrustDnsHttpConnector`].
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{self, Poll},
+    time::Duration,
+};
+
+use hyper::{service::Service, Uri};
+use socket2::{SockRef, TcpKeepalive};
+
+use crate::TrustDnsHttpConnector;
+
+/// A [`TrustDnsHttpConnector`] that configures TCP keepalive probes on every
+/// connection it establishes.
+///
+/// The probes are sent by the operating system while a connection is idle,
+/// for example while it waits in the pool of a hyper client. If the peer
+/// stops answering, the connection is closed and the pool drops it instead
+/// of handing out a dead connection. Unlike
+/// [`HttpConnector::set_keepalive`](hyper::client::HttpConnector::set_keepalive),
+/// this also allows setting the interval between probes.
+#[derive(Debug, Clone)]
+pub struct KeepaliveTrustDnsHttpConnector {
+    inner: TrustDnsHttpConnector,
+    keepalive: TcpKeepalive,
+}
+
+impl KeepaliveTrustDnsHttpConnector {
+    /// Create a new [`KeepaliveTrustDnsHttpConnector`] around `inner` that
+    /// starts sending probes after connections have been idle for `time`.
+    #[must_use]
+    pub fn new(inner: TrustDnsHttpConnector, time: Duration) -> Self {
+        Self {
+            inner,
+            keepalive: TcpKeepalive::new().with_time(time),
+        }
+    }
+
+    /// Set the time between probes that are not answered. Defaults to the
+    /// setting of the operating system.
+    #[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+    #[must_use]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.keepalive = self.keepalive.with_interval(interval);
+
+        self
+    }
+}
+
+impl Service<Uri> for KeepaliveTrustDnsHttpConnector {
+    type Response = <TrustDnsHttpConnector as Service<Uri>>::Response;
+    type Error = io::Error;
+    #[allow(clippy::type_complexity)]
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(io::Error::other)
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let connecting = self.inner.call(uri);
+        let keepalive = self.keepalive.clone();
+
+        Box::pin(async move {
+            let stream = connecting.await.map_err(io::Error::other)?;
+            SockRef::from(&stream).set_tcp_keepalive(&keepalive)?;
+
+            Ok(stream)
+        })
+    }
+}
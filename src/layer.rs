@@ -0,0 +1,128 @@
+//! A Tower layer that resolves names before passing them on.
+
+use std::{
+    error::Error,
+    future::Future,
+    mem,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use hyper::{client::connect::dns::Name, service::Service};
+use tower_layer::Layer;
+
+use crate::{SocketAddrs, TrustDnsResolver};
+
+type BoxError = Box<dyn Error + Send + Sync>;
+
+/// A [`Layer`] that resolves names with a [`TrustDnsResolver`] before passing
+/// the addresses on to the inner service.
+///
+/// The wrapped [`TrustDnsService`] accepts names as requests and calls the
+/// inner service, such as a service that connects to one of the addresses,
+/// with the [`SocketAddrs`] they resolved to.
+#[derive(Debug, Clone)]
+pub struct TrustDnsLayer {
+    resolver: TrustDnsResolver,
+}
+
+impl TrustDnsLayer {
+    /// Create a new [`TrustDnsLayer`] that resolves names with `resolver`.
+    #[must_use]
+    pub fn new(resolver: TrustDnsResolver) -> Self {
+        Self { resolver }
+    }
+}
+
+impl<S> Layer<S> for TrustDnsLayer {
+    type Service = TrustDnsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TrustDnsService {
+            resolver: self.resolver.clone(),
+            inner,
+        }
+    }
+}
+
+/// A service that resolves names and calls the inner service with the
+/// resulting addresses. Created by a [`TrustDnsLayer`].
+///
+/// Errors of the lookup and the inner service are returned boxed.
+#[derive(Debug, Clone)]
+pub struct TrustDnsService<S> {
+    resolver: TrustDnsResolver,
+    inner: S,
+}
+
+impl<S> TrustDnsService<S> {
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Consume this service and return the inner service.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S> TrustDnsService<S>
+where
+    S: Service<SocketAddrs> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+{
+    fn resolve_and_call(&mut self, name: String) -> <Self as Service<Name>>::Future {
+        // The inner service that was polled ready is used for this call
+        let clone = self.inner.clone();
+        let mut inner = mem::replace(&mut self.inner, clone);
+        let lookup = self.resolver.resolve(name);
+
+        Box::pin(async move {
+            let addrs = lookup.await?;
+
+            inner.call(addrs).await.map_err(Into::into)
+        })
+    }
+}
+
+impl<S> Service<Name> for TrustDnsService<S>
+where
+    S: Service<SocketAddrs> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    #[allow(clippy::type_complexity)]
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        self.resolve_and_call(name.as_str().to_owned())
+    }
+}
+
+#[cfg(feature = "hyper1")]
+impl<S> Service<hyper_util::client::legacy::connect::dns::Name> for TrustDnsService<S>
+where
+    S: Service<SocketAddrs> + Clone + Send + 'static,
+    S::Future: Send,
+    S::Error: Into<BoxError>,
+{
+    type Response = S::Response;
+    type Error = BoxError;
+    type Future = <Self as Service<Name>>::Future;
+
+    fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, name: hyper_util::client::legacy::connect::dns::Name) -> Self::Future {
+        self.resolve_and_call(name.as_str().to_owned())
+    }
+}
@@ -0,0 +1,78 @@
+//! Creating the resolver on first use.
+
+use std::{
+    fmt,
+    sync::{Arc, OnceLock},
+};
+
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+
+use crate::{
+    resolver::config::{ResolverConfig, ResolverOpts},
+    service::{impl_service, Resolve},
+    TrustDnsResolver,
+};
+
+/// A [`TrustDnsResolver`] that is only created on the first lookup.
+///
+/// Unlike the constructors of [`TrustDnsResolver`], this does not need a
+/// Tokio runtime context, so it can be created in `main` before the runtime
+/// is started or stored in a `static`. Lookups still have to run inside a
+/// Tokio runtime.
+///
+/// Clones share the same resolver once it was created.
+#[derive(Clone)]
+pub struct LazyTrustDnsResolver {
+    config: ResolverConfig,
+    options: ResolverOpts,
+    resolver: Arc<OnceLock<TrustDnsResolver>>,
+}
+
+impl LazyTrustDnsResolver {
+    /// Create a new [`LazyTrustDnsResolver`] with the resolver configuration
+    /// options specified.
+    #[must_use]
+    pub fn new(config: ResolverConfig, options: ResolverOpts) -> Self {
+        Self {
+            config,
+            options,
+            resolver: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+
+    /// Get the resolver, creating it if this is the first call.
+    // ResolverOpts is only Copy with the trust-dns backend
+    #[allow(clippy::clone_on_copy)]
+    fn get(&self) -> &TrustDnsResolver {
+        self.resolver.get_or_init(|| {
+            TrustDnsResolver::with_config_and_options(self.config.clone(), self.options.clone())
+        })
+    }
+}
+
+impl fmt::Debug for LazyTrustDnsResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyTrustDnsResolver")
+            .field("resolver", &self.resolver.get())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Resolve for LazyTrustDnsResolver {
+    type Future = <TrustDnsResolver as Service<Name>>::Future;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        self.get().resolve(name.to_owned())
+    }
+}
+
+impl_service!(LazyTrustDnsResolver);
@@ -3,43 +3,170 @@
 #![allow(clippy::module_name_repetitions)]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 
+#[cfg(not(any(feature = "hickory", feature = "trust-dns")))]
+compile_error!("either the `hickory` or the `trust-dns` feature must be enabled");
+
 use std::{
+    fmt,
     future::Future,
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::Arc,
     task::{self, Poll},
+    time::{Duration, Instant},
 };
 
+#[cfg(feature = "client")]
+use hyper::Client;
 use hyper::{
     client::{connect::dns::Name, HttpConnector},
     service::Service,
+    Uri,
 };
-use trust_dns_resolver::{
-    config::{ResolverConfig, ResolverOpts},
-    error::ResolveError,
-    lookup_ip::LookupIpIntoIter,
+
+/// The resolver crate backing this crate.
+///
+/// This is `hickory-resolver` if the `hickory` feature is enabled and
+/// `trust-dns-resolver` if only the `trust-dns` feature is enabled.
+#[cfg(feature = "hickory")]
+pub use hickory_resolver as resolver;
+/// The resolver crate backing this crate.
+///
+/// This is `hickory-resolver` if the `hickory` feature is enabled and
+/// `trust-dns-resolver` if only the `trust-dns` feature is enabled.
+#[cfg(all(feature = "trust-dns", not(feature = "hickory")))]
+pub use trust_dns_resolver as resolver;
+
+pub use ipnet::IpNet;
+pub use resolver::error::ResolveError;
+
+mod addrs;
+mod breaker;
+mod builder;
+mod cache;
+mod connector;
+mod ecs;
+#[cfg(any(
+    feature = "dns-over-https-rustls",
+    feature = "dns-over-rustls",
+    feature = "dns-over-native-tls",
+    feature = "dns-over-openssl"
+))]
+mod endpoint;
+mod env;
+mod error;
+mod fallback;
+#[cfg(feature = "hyper1")]
+pub mod hyper1;
+#[cfg(feature = "keepalive")]
+mod keepalive;
+#[cfg(feature = "tower-layer")]
+mod layer;
+mod lazy;
+mod limit;
+mod lookup;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "mock")]
+mod mock;
+mod multi;
+mod name_servers;
+mod opts;
+#[cfg(feature = "opentelemetry")]
+mod otel;
+mod pool;
+mod prefetch;
+mod providers;
+mod rate_limit;
+mod records;
+#[cfg(feature = "reqwest")]
+mod reqwest_ext;
+mod service;
+mod shared;
+mod shutdown;
+#[cfg(feature = "socks5")]
+mod socks5;
+mod split;
+#[cfg(feature = "futures")]
+mod stream;
+#[cfg(feature = "toml-config")]
+mod toml_config;
+
+pub use addrs::{SocketAddrList, SocketAddrs};
+pub use breaker::CircuitBreakerResolver;
+pub use builder::{BuildError, TrustDnsResolverBuilder};
+pub use cache::{CacheStats, CachingTrustDnsResolver};
+pub use connector::HttpConnectorOptions;
+#[cfg(feature = "native-tls")]
+pub use connector::NativeTlsHttpsConnectorBuilder;
+#[cfg(all(
+    any(feature = "rustls-native", feature = "rustls-webpki"),
+    any(feature = "rustls-http1", feature = "rustls-http2")
+))]
+pub use connector::RustlsHttpsConnectorBuilder;
+pub use connector::TrustDnsHttpConnectorBuilder;
+#[cfg(feature = "dns-over-https-rustls")]
+pub use endpoint::DohEndpointError;
+pub use env::EnvConfigError;
+pub use error::{resolve_error_to_io, DnsConnectError};
+pub use fallback::FallbackTrustDnsResolver;
+#[cfg(feature = "keepalive")]
+pub use keepalive::KeepaliveTrustDnsHttpConnector;
+#[cfg(feature = "tower-layer")]
+pub use layer::{TrustDnsLayer, TrustDnsService};
+pub use lazy::LazyTrustDnsResolver;
+pub use limit::ConcurrentLookupLimiter;
+use lookup::LookupSettings;
+#[cfg(feature = "metrics")]
+pub use metrics::TrustDnsMetrics;
+#[cfg(all(
+    feature = "mock",
+    any(feature = "rustls-native", feature = "rustls-webpki")
+))]
+pub use mock::LocalHttpsConnector;
+#[cfg(feature = "mock")]
+pub use mock::{LocalHttpConnector, LocalResolver, TrustDnsMockResolver};
+pub use multi::MultiProviderResolver;
+pub use opts::TrustDnsResolverOpts;
+pub use pool::ResolverPool;
+pub use prefetch::PrefetchingResolver;
+pub use providers::DnsProvider;
+pub use rate_limit::RateLimitedResolver;
+pub use records::SrvRecord;
+#[cfg(feature = "reqwest")]
+pub use reqwest_ext::ReqwestClientBuilderExt;
+use resolver::{
+    config::{LookupIpStrategy, NameServerConfig, Protocol, ResolverConfig, ResolverOpts},
     TokioAsyncResolver,
 };
+pub use shared::SharedTrustDnsResolver;
+pub use shutdown::TrustDnsResolverHandle;
+#[cfg(feature = "socks5")]
+pub use socks5::Socks5TrustDnsResolver;
+pub use split::{Matcher, SplitDnsTrustDnsResolver};
+#[cfg(feature = "toml-config")]
+pub use toml_config::{ConfigError, ResolverTomlConfig};
 
-/// A hyper resolver using `trust-dns`'s [`TokioAsyncResolver`].
+/// A hyper resolver using `hickory-dns`'s [`TokioAsyncResolver`].
+///
+/// Cloning the resolver is cheap, all clones share the same underlying
+/// resolver and cache. A resolver behind an [`Arc`] can be turned back into a
+/// [`TrustDnsResolver`] with [`From`] to use it as a hyper service.
 #[derive(Clone)]
 pub struct TrustDnsResolver {
     resolver: Arc<TokioAsyncResolver>,
+    settings: Arc<LookupSettings>,
+    /// The nameservers of the config the resolver was created with, or `None`
+    /// if it wraps an existing resolver.
+    name_servers: Option<Arc<[SocketAddr]>>,
+    on_resolve: Option<OnResolve>,
+    shutdown: Option<Arc<shutdown::Shutdown>>,
 }
 
-/// Iterator over DNS lookup results.
-pub struct SocketAddrs {
-    iter: LookupIpIntoIter,
-}
-
-impl Iterator for SocketAddrs {
-    type Item = SocketAddr;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        self.iter.next().map(|ip_addr| SocketAddr::new(ip_addr, 0))
-    }
-}
+/// A callback that is invoked after each lookup, see
+/// [`TrustDnsResolver::with_on_resolve`].
+type OnResolve =
+    Arc<dyn Fn(&Name, Result<&SocketAddrs, &ResolveError>, Duration) + Send + Sync + 'static>;
 
 impl TrustDnsResolver {
     /// Create a new [`TrustDnsResolver`] with the default config options.
@@ -49,6 +176,17 @@ impl TrustDnsResolver {
         Self::default()
     }
 
+    /// Create a new [`TrustDnsResolver`] with the default config options.
+    /// See [`TrustDnsResolver::try_with_config_and_options`] for details.
+    /// This must be run inside a Tokio runtime context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the resolver cannot be created.
+    pub fn try_new() -> Result<Self, ResolveError> {
+        Self::try_with_config_and_options(ResolverConfig::default(), ResolverOpts::default())
+    }
+
     /// Create a new [`TrustDnsResolver`] that uses the Google nameservers.
     /// This must be run inside a Tokio runtime context.
     #[must_use]
@@ -56,6 +194,28 @@ impl TrustDnsResolver {
         Self::with_config_and_options(ResolverConfig::google(), ResolverOpts::default())
     }
 
+    /// Create a new [`TrustDnsResolver`] that uses the Google nameservers.
+    /// This limits the registered connections to just HTTPS lookups.
+    /// This must be run inside a Tokio runtime context.
+    #[cfg(feature = "dns-over-https-rustls")]
+    #[must_use]
+    pub fn google_https() -> Self {
+        Self::with_config_and_options(ResolverConfig::google_https(), ResolverOpts::default())
+    }
+
+    /// Create a new [`TrustDnsResolver`] that uses the Google nameservers.
+    /// This limits the registered connections to just TLS lookups.
+    /// This must be run inside a Tokio runtime context.
+    #[cfg(any(
+        feature = "dns-over-rustls",
+        feature = "dns-over-native-tls",
+        feature = "dns-over-openssl"
+    ))]
+    #[must_use]
+    pub fn google_tls() -> Self {
+        Self::with_config_and_options(ResolverConfig::google_tls(), ResolverOpts::default())
+    }
+
     /// Create a new [`TrustDnsResolver`] that uses the Cloudflare nameservers.
     /// This must be run inside a Tokio runtime context.
     #[must_use]
@@ -114,44 +274,609 @@ impl TrustDnsResolver {
         Self::with_config_and_options(ResolverConfig::quad9_tls(), ResolverOpts::default())
     }
 
+    /// Create a new [`TrustDnsResolver`] that uses the OpenDNS (Cisco Umbrella)
+    /// nameservers.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn opendns() -> Self {
+        Self::with_config_and_options(providers::opendns(), ResolverOpts::default())
+    }
+
+    /// Create a new [`TrustDnsResolver`] that uses the OpenDNS (Cisco Umbrella)
+    /// nameservers.
+    /// This limits the registered connections to just HTTPS lookups.
+    /// This must be run inside a Tokio runtime context.
+    #[cfg(feature = "dns-over-https-rustls")]
+    #[must_use]
+    pub fn opendns_https() -> Self {
+        Self::with_config_and_options(providers::opendns_https(), ResolverOpts::default())
+    }
+
+    /// Create a new [`TrustDnsResolver`] that uses the AdGuard nameservers.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn adguard() -> Self {
+        Self::with_config_and_options(providers::adguard(), ResolverOpts::default())
+    }
+
+    /// Create a new [`TrustDnsResolver`] that uses the AdGuard nameservers.
+    /// This limits the registered connections to just HTTPS lookups.
+    /// This must be run inside a Tokio runtime context.
+    #[cfg(feature = "dns-over-https-rustls")]
+    #[must_use]
+    pub fn adguard_https() -> Self {
+        Self::with_config_and_options(providers::adguard_https(), ResolverOpts::default())
+    }
+
+    /// Create a new [`TrustDnsResolver`] that uses the AdGuard nameservers.
+    /// This limits the registered connections to just TLS lookups.
+    /// This must be run inside a Tokio runtime context.
+    #[cfg(any(
+        feature = "dns-over-rustls",
+        feature = "dns-over-native-tls",
+        feature = "dns-over-openssl"
+    ))]
+    #[must_use]
+    pub fn adguard_tls() -> Self {
+        Self::with_config_and_options(providers::adguard_tls(), ResolverOpts::default())
+    }
+
+    /// Create a new [`TrustDnsResolver`] that uses the AdGuard nameservers.
+    /// This limits the registered connections to just QUIC lookups (RFC 9250).
+    ///
+    /// DNS-over-QUIC is experimental in the resolver backend.
+    /// This must be run inside a Tokio runtime context.
+    #[cfg(feature = "dns-over-quic")]
+    #[must_use]
+    pub fn adguard_quic() -> Self {
+        Self::with_config_and_options(providers::adguard_quic(), ResolverOpts::default())
+    }
+
+    /// Create a new [`TrustDnsResolver`] with the default config options that
+    /// sends the EDNS Client Subnet option with `subnet` in its queries.
+    ///
+    /// **This discloses the subnet to the nameservers.** See
+    /// [`TrustDnsResolverBuilder::edns_client_subnet`] for the privacy
+    /// implications and the limitations of these lookups.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn with_edns_client_subnet(subnet: IpNet) -> Self {
+        Self::builder().edns_client_subnet(subnet).build_unchecked()
+    }
+
+    /// Create a new [`TrustDnsResolver`] with the default config options and
+    /// TCP fallback enabled or disabled. See
+    /// [`TrustDnsResolverBuilder::tcp_fallback`] for what this changes.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn with_tcp_fallback(enabled: bool) -> Self {
+        Self::builder().tcp_fallback(enabled).build_unchecked()
+    }
+
+    /// Create a new [`TrustDnsResolver`] with the default config options that
+    /// queries the nameservers in a random order if `enabled` is true. See
+    /// [`TrustDnsResolverBuilder::shuffle_dns_servers`].
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn with_shuffle_dns_servers(enabled: bool) -> Self {
+        Self::builder()
+            .shuffle_dns_servers(enabled)
+            .build_unchecked()
+    }
+
+    /// Create a new [`TrustDnsResolver`] for the DNS provider `provider`.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn from_provider(provider: DnsProvider) -> Self {
+        let (config, options) = provider.into_parts();
+
+        Self::with_config_and_options(config, options)
+    }
+
+    /// Create a new [`TrustDnsResolver`] with the default config options that
+    /// only looks up IPv4 addresses.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn with_ipv4_only() -> Self {
+        Self::builder().ipv4_only(true).build_unchecked()
+    }
+
+    /// Create a new [`TrustDnsResolver`] with the default config options that
+    /// prefers IPv6 addresses and only falls back to IPv4 if there are none.
+    ///
+    /// Since only one address family is returned per lookup, the Happy
+    /// Eyeballs interleaving of [`TrustDnsResolverBuilder::happy_eyeballs`]
+    /// has no effect for this resolver.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn with_ipv6_preferred() -> Self {
+        Self::builder()
+            .ip_strategy(LookupIpStrategy::Ipv6thenIpv4)
+            .build_unchecked()
+    }
+
+    /// Create a new [`TrustDnsResolver`] with the default config options that
+    /// looks up both IPv4 and IPv6 addresses and returns the IPv4 addresses
+    /// first. See [`TrustDnsResolverBuilder::prefer_ipv4`] for details.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn with_ipv4_preferred() -> Self {
+        Self::builder()
+            .ip_strategy(LookupIpStrategy::Ipv4AndIpv6)
+            .prefer_ipv4(true)
+            .build_unchecked()
+    }
+
+    /// Create a new [`TrustDnsResolver`] that uses the given nameservers.
+    /// Each nameserver is queried via UDP first, falling back to TCP.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn with_nameservers(servers: &[SocketAddr]) -> Self {
+        let name_servers: Vec<NameServerConfig> = servers
+            .iter()
+            .flat_map(|&server| {
+                [
+                    NameServerConfig::new(server, Protocol::Udp),
+                    NameServerConfig::new(server, Protocol::Tcp),
+                ]
+            })
+            .collect();
+        let config = ResolverConfig::from_parts(None, vec![], name_servers);
+
+        Self::with_config_and_options(config, ResolverOpts::default())
+    }
+
+    /// Create a new [`TrustDnsResolver`] that uses a local DNS-over-HTTPS
+    /// proxy listening on `127.0.0.1:port`, like `cloudflared proxy-dns`,
+    /// which listens on port 5053 by default.
+    ///
+    /// These proxies accept plain DNS queries and forward them over HTTPS, so
+    /// the resolver sends plain queries via UDP, falling back to TCP, and does
+    /// not need any DNS-over-HTTPS support itself.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    pub fn local_doh(port: u16) -> Self {
+        Self::with_nameservers(&[SocketAddr::from(([127, 0, 0, 1], port))])
+    }
+
+    /// Create a new [`TrustDnsResolver`] that uses the DNS-over-HTTPS server
+    /// at `url`, like `https://resolver.internal/dns-query`.
+    ///
+    /// If the host of the URL is not an IP address, it is resolved once with
+    /// the system resolver, which blocks the current thread.
+    /// This must be run inside a Tokio runtime context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is invalid, has a path other than
+    /// `/dns-query` or its host cannot be resolved.
+    #[cfg(feature = "dns-over-https-rustls")]
+    pub fn with_doh_endpoint(url: &str) -> Result<Self, DohEndpointError> {
+        let config = endpoint::doh_config(url)?;
+
+        Ok(Self::with_config_and_options(
+            config,
+            ResolverOpts::default(),
+        ))
+    }
+
+    /// Create a new [`TrustDnsResolver`] that uses the DNS-over-TLS server at
+    /// `ip` and `port`, verifying its certificate against `tls_name`.
+    /// This must be run inside a Tokio runtime context.
+    #[cfg(any(
+        feature = "dns-over-rustls",
+        feature = "dns-over-native-tls",
+        feature = "dns-over-openssl"
+    ))]
+    #[must_use]
+    pub fn with_dot_endpoint(ip: std::net::IpAddr, port: u16, tls_name: &str) -> Self {
+        Self::with_config_and_options(
+            endpoint::dot_config(ip, port, tls_name),
+            ResolverOpts::default(),
+        )
+    }
+
     /// Create a new [`TrustDnsResolver`] with the resolver configuration
     /// options specified.
     /// This must be run inside a Tokio runtime context.
-    #[allow(clippy::missing_panics_doc)]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `config` has no nameservers. See
+    /// [`TrustDnsResolver::try_with_config_and_options`] for a constructor
+    /// that returns an error instead.
     #[must_use]
     pub fn with_config_and_options(config: ResolverConfig, options: ResolverOpts) -> Self {
-        // This unwrap is safe because internally, there is nothing to be unwrapped
-        // TokioAsyncResolver::new cannot return Err
-        let resolver = Arc::new(TokioAsyncResolver::tokio(config, options).unwrap());
+        Self::try_with_config_and_options(config, options)
+            .expect("failed to create the resolver with the given config and options")
+    }
+
+    /// Create a new [`TrustDnsResolver`] with the resolver configuration
+    /// options specified, sending all queries to `port` instead of the ports
+    /// of the nameservers in `config`, for example to reach a local resolver
+    /// on port 5353.
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    // Takes the config by value like `with_config_and_options`
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn with_config_and_options_and_port(
+        config: ResolverConfig,
+        options: ResolverOpts,
+        port: u16,
+    ) -> Self {
+        let name_servers: Vec<NameServerConfig> = config
+            .name_servers()
+            .iter()
+            .cloned()
+            .map(|mut name_server| {
+                name_server.socket_addr.set_port(port);
+
+                name_server
+            })
+            .collect();
+        let config = ResolverConfig::from_parts(
+            config.domain().cloned(),
+            config.search().to_vec(),
+            name_servers,
+        );
+
+        Self::with_config_and_options(config, options)
+    }
+
+    /// Create a new [`LazyTrustDnsResolver`] with the resolver configuration
+    /// options specified. The resolver is only created on the first lookup, so
+    /// unlike the other constructors, this can be run outside of a Tokio
+    /// runtime context.
+    #[must_use]
+    pub fn new_lazy(config: ResolverConfig, options: ResolverOpts) -> LazyTrustDnsResolver {
+        LazyTrustDnsResolver::new(config, options)
+    }
+
+    /// Create a new [`TrustDnsResolver`] from an existing
+    /// [`TokioAsyncResolver`], for example to share it between multiple
+    /// clients.
+    #[must_use]
+    pub fn from_resolver(resolver: Arc<TokioAsyncResolver>) -> Self {
+        Self::builder().build_with_resolver(resolver)
+    }
+
+    /// Get the underlying [`TokioAsyncResolver`], for example to look up
+    /// other record types than IP addresses.
+    #[must_use]
+    pub fn inner(&self) -> &Arc<TokioAsyncResolver> {
+        &self.resolver
+    }
+
+    /// Remove all entries from the cache of the resolver, shared by all of
+    /// its clones, so that the next lookups query the nameservers again, for
+    /// example after a deployment changed some records.
+    ///
+    /// There is no `clear_cache_for` to remove the entries of a single name:
+    /// the [`TokioAsyncResolver`] of the backend only exposes `clear_cache`
+    /// and keeps its cache private. To invalidate single names, put a
+    /// [`CachingTrustDnsResolver`] in front of a resolver without a cache
+    /// and use [`CachingTrustDnsResolver::clear_cache_for`].
+    pub fn clear_cache(&self) {
+        self.resolver.clear_cache();
+    }
+
+    /// Look up the addresses of `name`, the same way as the lookups made by
+    /// hyper. The ports of the returned addresses are 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lookup fails.
+    pub async fn lookup_ip(&self, name: &str) -> Result<SocketAddrs, ResolveError> {
+        self.settings.lookup_ip(&self.resolver, name).await
+    }
+
+    /// Look up the addresses of `name` as a hyper service would and stream
+    /// them one by one, in the order they would be connected to.
+    ///
+    /// The addresses become available once the lookup completes. If it fails,
+    /// the stream yields the error and ends. The ports of the addresses are 0.
+    #[cfg(feature = "futures")]
+    pub fn resolve_stream(
+        &self,
+        name: &Name,
+    ) -> impl futures_core::Stream<Item = Result<SocketAddr, ResolveError>> + Send {
+        stream::ResolveStream::Pending(self.resolve(name.as_str().to_owned()))
+    }
+
+    /// Look up `names` in the background to fill the cache and open the
+    /// connections to the nameservers before the first request needs them.
+    ///
+    /// The lookups are spawned onto the Tokio runtime right away and run in
+    /// parallel, the returned future only waits for them to finish. Failed
+    /// lookups are ignored.
+    pub fn pre_warm(&self, names: &[&str]) -> impl Future<Output = ()> + Send + 'static {
+        let lookups: Vec<_> = names
+            .iter()
+            .map(|&name| {
+                let resolver = self.clone();
+                let name = name.to_owned();
+
+                tokio::spawn(async move {
+                    let _ = resolver.lookup_ip(&name).await;
+                })
+            })
+            .collect();
+
+        async move {
+            for lookup in lookups {
+                let _ = lookup.await;
+            }
+        }
+    }
+
+    /// Check that the nameservers are reachable by looking up a well-known
+    /// domain, and return how long the lookup took.
+    ///
+    /// The domain can be set with
+    /// [`TrustDnsResolverBuilder::health_check_domain`]. Note that the lookup
+    /// is answered from the cache while the records of the domain are cached,
+    /// so only the first check measures the round-trip time to the
+    /// nameservers.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the lookup if it fails.
+    pub async fn health_check(&self) -> Result<Duration, ResolveError> {
+        let start = Instant::now();
 
-        Self { resolver }
+        self.lookup_ip(&self.settings.health_check_domain).await?;
+
+        Ok(start.elapsed())
+    }
+
+    /// Call `f` after each lookup made as a hyper service with the name, the
+    /// result and how long the lookup took, for example to record metrics.
+    /// This replaces any previous callback.
+    #[must_use]
+    pub fn with_on_resolve(
+        mut self,
+        f: impl Fn(&Name, Result<&SocketAddrs, &ResolveError>, Duration) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_resolve = Some(Arc::new(f));
+
+        self
+    }
+
+    /// Create a [`TrustDnsResolverHandle`] to shut down this resolver and all
+    /// of its clones gracefully. Only lookups made as a hyper service are
+    /// tracked by the handle. This replaces any previous handle.
+    #[must_use]
+    pub fn with_shutdown_handle(mut self) -> (Self, TrustDnsResolverHandle) {
+        let shutdown = Arc::new(shutdown::Shutdown::new());
+        self.shutdown = Some(shutdown.clone());
+
+        (self, TrustDnsResolverHandle::new(shutdown))
+    }
+
+    /// Create a new [`TrustDnsResolver`] with the resolver configuration
+    /// options specified.
+    ///
+    /// The settings are validated like in [`TrustDnsResolverBuilder::build`].
+    /// This must be run inside a Tokio runtime context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the resolver cannot be created, for example
+    /// because `config` has no nameservers.
+    pub fn try_with_config_and_options(
+        config: ResolverConfig,
+        options: ResolverOpts,
+    ) -> Result<Self, ResolveError> {
+        Self::builder()
+            .config(config)
+            .options(options)
+            .build()
+            .map_err(|e| ResolveError::from(e.to_string()))
+    }
+
+    /// Create a new [`TrustDnsResolverBuilder`] to configure a
+    /// [`TrustDnsResolver`] step by step.
+    pub fn builder() -> TrustDnsResolverBuilder {
+        TrustDnsResolverBuilder::new()
     }
 
     /// Create a new [`TrustDnsResolver`] with the system configuration.
+    /// See [`TrustDnsResolver::try_from_system_conf`] for details.
     /// This must be run inside a Tokio runtime context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the system configuration cannot be read.
     #[cfg(feature = "system-config")]
-    #[allow(clippy::missing_panics_doc)]
     #[must_use]
     pub fn from_system_conf() -> Self {
-        // This unwrap is safe because internally, there is nothing to be unwrapped
-        // TokioAsyncResolver::new cannot return Err
-        let resolver = Arc::new(TokioAsyncResolver::tokio_from_system_conf().unwrap());
+        Self::try_from_system_conf().expect("failed to read the system DNS configuration")
+    }
 
-        Self { resolver }
+    /// Create a new [`TrustDnsResolver`] with the system configuration.
+    ///
+    /// On Unix, the configuration is read from `/etc/resolv.conf`. On Windows,
+    /// the nameservers of the network adapters are read from the registry.
+    /// The hosts file is used in addition to the nameservers on both.
+    /// This must be run inside a Tokio runtime context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system configuration cannot be read, for
+    /// example because `/etc/resolv.conf` is missing or malformed.
+    #[cfg(feature = "system-config")]
+    pub fn try_from_system_conf() -> Result<Self, ResolveError> {
+        let resolver = TokioAsyncResolver::tokio_from_system_conf()?;
+
+        Ok(Self::from_resolver(Arc::new(resolver)))
+    }
+
+    /// Create a new [`TrustDnsResolver`] with the system configuration, or
+    /// with the default config options if it cannot be read.
+    /// See [`TrustDnsResolver::try_from_system_conf`] for details.
+    /// This must be run inside a Tokio runtime context.
+    ///
+    /// With the `tracing` feature, a warning is logged when falling back.
+    #[cfg(feature = "system-config")]
+    #[must_use]
+    pub fn from_system_conf_or_default() -> Self {
+        let result = Self::try_from_system_conf();
+
+        #[cfg(feature = "tracing")]
+        if let Err(e) = &result {
+            tracing::warn!(error = %e, "failed to read the system DNS configuration, using the default");
+        }
+
+        result.unwrap_or_default()
+    }
+
+    /// Create a new [`TrustDnsResolver`] configured by environment variables,
+    /// for example in container deployments:
+    ///
+    /// - `TRUST_DNS_NAMESERVER`: comma-separated nameservers like
+    ///   `8.8.8.8:53,1.1.1.1:53`. The port defaults to the one of the
+    ///   protocol.
+    /// - `TRUST_DNS_PROTOCOL`: `udp` (the default, falling back to TCP),
+    ///   `tcp`, `tls` or `https`. The latter two require one of the
+    ///   DNS-over-TLS or the DNS-over-HTTPS features.
+    /// - `TRUST_DNS_TLS_NAME`: the TLS name of the nameservers like
+    ///   `dns.google`, required for `tls` and `https`.
+    ///
+    /// This must be run inside a Tokio runtime context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the variables are missing or invalid.
+    pub fn from_env() -> Result<Self, EnvConfigError> {
+        let config = env::config_from_env()?;
+
+        Ok(Self::with_config_and_options(
+            config,
+            ResolverOpts::default(),
+        ))
+    }
+
+    /// Create a new [`TrustDnsResolver`] with the configuration in the TOML
+    /// file at `path`. See [`ResolverTomlConfig`] for the format.
+    /// This must be run inside a Tokio runtime context.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or the configuration is
+    /// invalid.
+    #[cfg(feature = "toml-config")]
+    pub fn from_toml_file(path: impl AsRef<std::path::Path>) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        let (config, options) = contents.parse::<ResolverTomlConfig>()?.into_parts()?;
+
+        Ok(Self::with_config_and_options(config, options))
     }
 
     /// Create a new [`TrustDnsHttpConnector`] with this resolver.
+    ///
+    /// The connector only accepts `http` URIs. To wrap it in a TLS
+    /// connector, use [`TrustDnsResolver::into_http_connector_for_tls`]
+    /// instead.
     #[must_use]
     pub fn into_http_connector(self) -> TrustDnsHttpConnector {
-        TrustDnsHttpConnector::new_with_resolver(self)
+        TrustDnsHttpConnector::from(self)
+    }
+
+    /// Create a new [`TrustDnsHttpConnector`] with this resolver that also
+    /// accepts `https` URIs, so that it can be wrapped in a TLS connector.
+    #[must_use]
+    pub fn into_http_connector_for_tls(self) -> TrustDnsHttpConnector {
+        let mut http_connector = self.into_http_connector();
+        http_connector.enforce_http(false);
+
+        http_connector
+    }
+
+    /// Create a new [`TrustDnsHttpConnectorBuilder`] to configure a
+    /// [`TrustDnsHttpConnector`] with this resolver.
+    pub fn into_http_connector_builder(self) -> TrustDnsHttpConnectorBuilder {
+        TrustDnsHttpConnectorBuilder::new(self)
+    }
+
+    /// Create a new [`TrustDnsHttpConnector`] with this resolver and the
+    /// settings in `options`.
+    #[must_use]
+    pub fn into_http_connector_with_options(
+        self,
+        options: &HttpConnectorOptions,
+    ) -> TrustDnsHttpConnector {
+        options.build(self)
+    }
+
+    /// Create a new [`TrustDnsHttpConnector`] with this resolver that binds
+    /// connections to the local address `addr`, for example to pick the
+    /// outgoing interface on hosts with multiple addresses.
+    #[must_use]
+    pub fn into_http_connector_with_local_address(self, addr: IpAddr) -> TrustDnsHttpConnector {
+        HttpConnectorOptions {
+            local_address: Some(addr),
+            ..HttpConnectorOptions::default()
+        }
+        .build(self)
+    }
+
+    /// Create a new [`TrustDnsHttpConnector`] with this resolver that gives up
+    /// on establishing a TCP connection after `timeout`.
+    #[must_use]
+    pub fn into_http_connector_with_timeout(self, timeout: Duration) -> TrustDnsHttpConnector {
+        HttpConnectorOptions {
+            connect_timeout: Some(timeout),
+            ..HttpConnectorOptions::default()
+        }
+        .build(self)
+    }
+
+    /// Create a new [`TrustDnsHttpConnector`] with this resolver that enables
+    /// TCP keepalive with the idle time `keepalive` and disables Nagle's
+    /// algorithm, the typical settings for long-lived connections through
+    /// NAT devices.
+    ///
+    /// [`TrustDnsHttpConnector`] is an alias of a hyper type, so this cannot
+    /// be a constructor of the connector itself.
+    #[must_use]
+    pub fn into_http_connector_with_keepalive(self, keepalive: Duration) -> TrustDnsHttpConnector {
+        HttpConnectorOptions {
+            keepalive: Some(keepalive),
+            nodelay: true,
+            ..HttpConnectorOptions::default()
+        }
+        .build(self)
+    }
+
+    /// Create a new [`Client`] that uses a [`TrustDnsHttpConnector`] with
+    /// this resolver and the default client settings.
+    #[cfg(feature = "client")]
+    #[must_use]
+    pub fn into_hyper_client(self) -> Client<TrustDnsHttpConnector> {
+        Client::builder().build(self.into_http_connector())
+    }
+
+    /// Create a new [`Client`] that uses a [`RustlsHttpsConnector`] with the
+    /// `webpki_roots` and the default client settings.
+    #[cfg(all(feature = "client", feature = "rustls-webpki"))]
+    #[must_use]
+    pub fn into_hyper_https_client_rustls_webpki(self) -> Client<RustlsHttpsConnector> {
+        Client::builder().build(self.into_rustls_webpki_https_connector())
+    }
+
+    /// Create a new [`Client`] that uses a [`NativeTlsHttpsConnector`] and
+    /// the default client settings.
+    #[cfg(all(feature = "client", feature = "native-tls"))]
+    #[must_use]
+    pub fn into_hyper_https_client_native_tls(self) -> Client<NativeTlsHttpsConnector> {
+        Client::builder().build(self.into_native_tls_https_connector())
     }
 
     /// Create a new [`NativeTlsHttpsConnector`].
     #[cfg(feature = "native-tls")]
     #[must_use]
     pub fn into_native_tls_https_connector(self) -> NativeTlsHttpsConnector {
-        let mut http_connector = self.into_http_connector();
-        http_connector.enforce_http(false);
+        let http_connector = self.into_http_connector_for_tls();
 
         let mut native_https_connector =
             NativeTlsHttpsConnector::new_with_connector(http_connector);
@@ -165,12 +890,41 @@ impl TrustDnsResolver {
         native_https_connector
     }
 
+    /// Create a new [`NativeTlsHttpsConnector`] using a custom
+    /// [`TlsConnector`](tokio_native_tls::native_tls::TlsConnector), for
+    /// example to use custom root certificates or client certificates.
+    #[cfg(feature = "native-tls")]
+    #[must_use]
+    pub fn into_native_tls_https_connector_with_config(
+        self,
+        tls: tokio_native_tls::native_tls::TlsConnector,
+    ) -> NativeTlsHttpsConnector {
+        let http_connector = self.into_http_connector_for_tls();
+
+        let mut native_https_connector =
+            NativeTlsHttpsConnector::from((http_connector, tls.into()));
+
+        #[cfg(feature = "https-only")]
+        native_https_connector.https_only(true);
+
+        #[cfg(not(feature = "https-only"))]
+        native_https_connector.https_only(false);
+
+        native_https_connector
+    }
+
+    /// Create a new [`NativeTlsHttpsConnectorBuilder`] to configure a
+    /// [`NativeTlsHttpsConnector`] with this resolver.
+    #[cfg(feature = "native-tls")]
+    pub fn into_native_tls_https_connector_builder(self) -> NativeTlsHttpsConnectorBuilder {
+        NativeTlsHttpsConnectorBuilder::new(self)
+    }
+
     /// Create a new [`RustlsHttpsConnector`] using the OS root store.
     #[cfg(feature = "rustls-native")]
     #[must_use]
     pub fn into_rustls_native_https_connector(self) -> RustlsHttpsConnector {
-        let mut http_connector = self.into_http_connector();
-        http_connector.enforce_http(false);
+        let http_connector = self.into_http_connector_for_tls();
 
         let builder = hyper_rustls::HttpsConnectorBuilder::new().with_native_roots();
 
@@ -193,8 +947,7 @@ impl TrustDnsResolver {
     #[cfg(feature = "rustls-webpki")]
     #[must_use]
     pub fn into_rustls_webpki_https_connector(self) -> RustlsHttpsConnector {
-        let mut http_connector = self.into_http_connector();
-        http_connector.enforce_http(false);
+        let http_connector = self.into_http_connector_for_tls();
 
         let builder = hyper_rustls::HttpsConnectorBuilder::new().with_webpki_roots();
 
@@ -212,6 +965,125 @@ impl TrustDnsResolver {
 
         builder.wrap_connector(http_connector)
     }
+
+    /// Create a new [`RustlsHttpsConnector`] using a custom rustls
+    /// [`ClientConfig`](rustls::ClientConfig), for example to use custom root
+    /// certificates or client certificates.
+    ///
+    /// The ALPN protocols of `tls_config` are replaced with the ones enabled
+    /// by the `rustls-http1` and `rustls-http2` features.
+    #[cfg(any(feature = "rustls-native", feature = "rustls-webpki"))]
+    #[must_use]
+    pub fn into_rustls_https_connector_with_config(
+        self,
+        mut tls_config: rustls::ClientConfig,
+    ) -> RustlsHttpsConnector {
+        let http_connector = self.into_http_connector_for_tls();
+
+        tls_config.alpn_protocols.clear();
+
+        let builder = hyper_rustls::HttpsConnectorBuilder::new().with_tls_config(tls_config);
+
+        #[cfg(feature = "https-only")]
+        let builder = builder.https_only();
+
+        #[cfg(not(feature = "https-only"))]
+        let builder = builder.https_or_http();
+
+        #[cfg(feature = "rustls-http1")]
+        let builder = builder.enable_http1();
+
+        #[cfg(feature = "rustls-http2")]
+        let builder = builder.enable_http2();
+
+        builder.wrap_connector(http_connector)
+    }
+
+    /// Create a new [`RustlsHttpsConnector`] that only trusts the root
+    /// certificates in `roots`, for example those of an internal PKI.
+    #[cfg(any(feature = "rustls-native", feature = "rustls-webpki"))]
+    #[must_use]
+    pub fn into_rustls_https_connector_with_roots(
+        self,
+        roots: rustls::RootCertStore,
+    ) -> RustlsHttpsConnector {
+        let tls_config = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        self.into_rustls_https_connector_with_config(tls_config)
+    }
+
+    /// Create a new [`RustlsHttpsConnectorBuilder`] to configure a
+    /// [`RustlsHttpsConnector`] with this resolver.
+    #[cfg(all(
+        any(feature = "rustls-native", feature = "rustls-webpki"),
+        any(feature = "rustls-http1", feature = "rustls-http2")
+    ))]
+    pub fn into_rustls_https_connector_builder(self) -> RustlsHttpsConnectorBuilder {
+        RustlsHttpsConnectorBuilder::new(self)
+    }
+
+    /// Resolve `name` into the [`SocketAddrs`] returned by the resolver
+    /// services for both hyper versions.
+    fn resolve(&self, name: String) -> <Self as Service<Name>>::Future {
+        // The returned future is 'static, so it has to own the resolver. A weak
+        // reference would not help, upgrading it is an atomic operation too.
+        let resolver = self.resolver.clone();
+        let settings = self.settings.clone();
+        let on_resolve = self.on_resolve.clone();
+        let in_flight = match &self.shutdown {
+            Some(shutdown) => match shutdown.start() {
+                Some(in_flight) => Some(in_flight),
+                None => {
+                    return Box::pin(async {
+                        Err(ResolveError::from("resolver is shut down").into())
+                    })
+                }
+            },
+            None => None,
+        };
+
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!("dns_resolve", name = %name);
+
+        #[cfg(feature = "opentelemetry")]
+        let otel_cx = otel::start_span(&name);
+
+        let lookup = async move {
+            let _in_flight = in_flight;
+            let start = Instant::now();
+            let result = settings.lookup_ip(&resolver, &name).await;
+
+            if let Some(on_resolve) = on_resolve {
+                if let Ok(name) = name.parse::<Name>() {
+                    on_resolve(&name, result.as_ref(), start.elapsed());
+                }
+            }
+
+            #[cfg(feature = "opentelemetry")]
+            otel::end_span(&result);
+
+            #[cfg(feature = "tracing")]
+            match &result {
+                Ok(addrs) => tracing::debug!(addrs = ?addrs.clone().into_vec(), "resolved"),
+                Err(e) => {
+                    tracing::warn!(error = %e, source = ?std::error::Error::source(e), "lookup failed");
+                }
+            }
+
+            result.map_err(DnsConnectError::from)
+        };
+
+        #[cfg(feature = "tracing")]
+        let lookup = tracing::Instrument::instrument(lookup, span);
+
+        #[cfg(feature = "opentelemetry")]
+        let lookup = opentelemetry::trace::FutureExt::with_context(lookup, otel_cx);
+
+        Box::pin(lookup)
+    }
 }
 
 impl Default for TrustDnsResolver {
@@ -220,9 +1092,36 @@ impl Default for TrustDnsResolver {
     }
 }
 
+impl fmt::Debug for TrustDnsResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrustDnsResolver")
+            .field("name_servers", &self.name_servers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<TrustDnsResolver> for TrustDnsHttpConnector {
+    fn from(resolver: TrustDnsResolver) -> Self {
+        Self::new_with_resolver(resolver)
+    }
+}
+
+impl AsRef<TokioAsyncResolver> for TrustDnsResolver {
+    fn as_ref(&self) -> &TokioAsyncResolver {
+        &self.resolver
+    }
+}
+
+/// Resolves names into socket addresses with port 0, which hyper replaces
+/// with the port of the URI. Internationalized domain names like
+/// `münchen.de` are converted to their ASCII form (`xn--mnchen-3ya.de`) by
+/// the resolver backend, so hyper can be given either.
 impl Service<Name> for TrustDnsResolver {
     type Response = SocketAddrs;
-    type Error = ResolveError;
+    type Error = DnsConnectError;
+    // The lookup futures of the resolver backend are returned by async fns and
+    // cannot be named, so a hand-written future would still have to box them.
+    // One allocation per lookup is negligible next to the query itself.
     #[allow(clippy::type_complexity)]
     type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
@@ -231,14 +1130,39 @@ impl Service<Name> for TrustDnsResolver {
     }
 
     fn call(&mut self, name: Name) -> Self::Future {
-        let resolver = self.resolver.clone();
+        self.resolve(name.as_str().to_owned())
+    }
+}
+
+/// Resolves the host of a URI into socket addresses with the port of the URI,
+/// or the default port of its scheme. This allows opening connections without
+/// going through a [`HttpConnector`].
+impl Service<Uri> for TrustDnsResolver {
+    type Response = SocketAddrs;
+    type Error = DnsConnectError;
+    type Future = <Self as Service<Name>>::Future;
+
+    fn poll_ready(&mut self, _cx: &mut task::Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let Some(host) = uri.host() else {
+            return Box::pin(async move {
+                Err(ResolveError::from(format!("{uri} has no host")).into())
+            });
+        };
+
+        // IPv6 addresses are enclosed in brackets
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let port = uri.port_u16().unwrap_or(match uri.scheme_str() {
+            Some("https") => 443,
+            _ => 80,
+        });
 
-        Box::pin(async move {
-            let response = resolver.lookup_ip(name.as_str()).await?;
-            let addresses = response.into_iter();
+        let lookup = self.resolve(host.to_owned());
 
-            Ok(SocketAddrs { iter: addresses })
-        })
+        Box::pin(async move { lookup.await.map(|addrs| addrs.with_port(port)) })
     }
 }
 
@@ -0,0 +1,127 @@
+//! Limiting the number of concurrent lookups.
+
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{self, Poll},
+};
+
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+use tokio::sync::{AcquireError, OwnedSemaphorePermit, Semaphore};
+
+use crate::{
+    service::{impl_service, Resolve},
+    DnsConnectError, ResolveError, TrustDnsResolver,
+};
+
+type Acquire =
+    Pin<Box<dyn Future<Output = Result<OwnedSemaphorePermit, AcquireError>> + Send + Sync>>;
+
+/// A resolver that caps the number of lookups of a [`TrustDnsResolver`] that
+/// are in flight at the same time.
+///
+/// Clones share the same limit. [`Service::poll_ready`] only returns ready
+/// once a lookup can be started, so hyper waits for a slot before calling
+/// the resolver.
+pub struct ConcurrentLookupLimiter {
+    inner: TrustDnsResolver,
+    semaphore: Arc<Semaphore>,
+    acquire: Option<Acquire>,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl ConcurrentLookupLimiter {
+    /// Create a new [`ConcurrentLookupLimiter`] that allows at most
+    /// `max_concurrent` lookups of `inner` at the same time.
+    #[must_use]
+    pub fn new(inner: TrustDnsResolver, max_concurrent: usize) -> Self {
+        Self {
+            inner,
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            acquire: None,
+            permit: None,
+        }
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+}
+
+impl Clone for ConcurrentLookupLimiter {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            semaphore: self.semaphore.clone(),
+            acquire: None,
+            permit: None,
+        }
+    }
+}
+
+impl fmt::Debug for ConcurrentLookupLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConcurrentLookupLimiter")
+            .field("inner", &self.inner)
+            .field("available", &self.semaphore.available_permits())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Resolve for ConcurrentLookupLimiter {
+    type Future = <TrustDnsResolver as Service<Name>>::Future;
+
+    fn poll_ready(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), DnsConnectError>> {
+        if self.permit.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+
+        let acquire = self
+            .acquire
+            .get_or_insert_with(|| Box::pin(self.semaphore.clone().acquire_owned()));
+
+        let permit = match acquire.as_mut().poll(cx) {
+            Poll::Ready(permit) => permit,
+            Poll::Pending => return Poll::Pending,
+        };
+        self.acquire = None;
+
+        match permit {
+            Ok(permit) => {
+                self.permit = Some(permit);
+
+                Poll::Ready(Ok(()))
+            }
+            Err(e) => Poll::Ready(Err(ResolveError::from(e.to_string()).into())),
+        }
+    }
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        let name = name.to_owned();
+        let inner = self.inner.clone();
+        let permit = self.permit.take();
+        let semaphore = self.semaphore.clone();
+
+        Box::pin(async move {
+            let _permit = match permit {
+                Some(permit) => permit,
+                None => semaphore
+                    .acquire_owned()
+                    .await
+                    .map_err(|e| DnsConnectError::from(ResolveError::from(e.to_string())))?,
+            };
+
+            // Only start the lookup once the permit is held
+            inner.resolve(name).await
+        })
+    }
+}
+
+impl_service!(ConcurrentLookupLimiter);
@@ -0,0 +1,347 @@
+use std::{
+    future::Future,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "metrics")]
+use crate::TrustDnsMetrics;
+use crate::{
+    addrs::AddrOrder,
+    ecs::ClientSubnetLookup,
+    resolver::{config::LookupIpStrategy, error::ResolveErrorKind, TokioAsyncResolver},
+    ResolveError, SocketAddrs,
+};
+
+/// Settings that apply to every lookup of a [`TrustDnsResolver`].
+///
+/// [`TrustDnsResolver`]: crate::TrustDnsResolver
+#[derive(Clone)]
+#[allow(clippy::struct_excessive_bools)]
+pub(crate) struct LookupSettings {
+    pub(crate) happy_eyeballs: bool,
+    pub(crate) prefer_ipv4: bool,
+    pub(crate) race_queries: bool,
+    pub(crate) ip_strategy: LookupIpStrategy,
+    pub(crate) timeout: Option<Duration>,
+    pub(crate) max_retries: u32,
+    pub(crate) backoff: Duration,
+    pub(crate) block_private_ips: bool,
+    pub(crate) health_check_domain: String,
+    pub(crate) client_subnet: Option<Arc<ClientSubnetLookup>>,
+    #[cfg(feature = "metrics")]
+    pub(crate) metrics: Option<TrustDnsMetrics>,
+}
+
+impl Default for LookupSettings {
+    fn default() -> Self {
+        Self {
+            happy_eyeballs: true,
+            prefer_ipv4: false,
+            race_queries: false,
+            ip_strategy: LookupIpStrategy::default(),
+            timeout: None,
+            max_retries: 0,
+            backoff: Duration::from_millis(100),
+            block_private_ips: false,
+            health_check_domain: String::from("one.one.one.one"),
+            client_subnet: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+        }
+    }
+}
+
+impl LookupSettings {
+    /// The order of the addresses of a lookup with these settings. With
+    /// happy eyeballs, the addresses start with IPv6 unless `ipv6_first` is
+    /// false.
+    pub(crate) fn order(&self, ipv6_first: bool) -> AddrOrder {
+        if self.prefer_ipv4 {
+            AddrOrder::Ipv4First
+        } else if self.happy_eyeballs {
+            AddrOrder::Interleaved { ipv6_first }
+        } else {
+            AddrOrder::Unchanged
+        }
+    }
+
+    /// Look up the addresses of `name` with these settings.
+    ///
+    /// Transient failures are retried with exponential backoff.
+    pub(crate) async fn lookup_ip(
+        &self,
+        resolver: &TokioAsyncResolver,
+        name: &str,
+    ) -> Result<SocketAddrs, ResolveError> {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            let start = Instant::now();
+            let result = self.lookup_ip_with_retries(resolver, name).await;
+            metrics.record(start.elapsed(), result.as_ref().err());
+
+            return result;
+        }
+
+        self.lookup_ip_with_retries(resolver, name).await
+    }
+
+    /// Look up the addresses of `name`, retrying transient failures.
+    async fn lookup_ip_with_retries(
+        &self,
+        resolver: &TokioAsyncResolver,
+        name: &str,
+    ) -> Result<SocketAddrs, ResolveError> {
+        let mut retries = 0;
+        let mut backoff = self.backoff;
+
+        loop {
+            match self.attempt(resolver, name).await {
+                Err(e) if retries < self.max_retries && is_transient(&e) => {
+                    tokio::time::sleep(backoff).await;
+
+                    retries += 1;
+                    backoff = backoff.saturating_mul(2);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Make a single attempt to look up `name`, subject to the timeout.
+    async fn attempt(
+        &self,
+        resolver: &TokioAsyncResolver,
+        name: &str,
+    ) -> Result<SocketAddrs, ResolveError> {
+        match self.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.query(resolver, name))
+                .await
+                .map_err(|_| ResolveErrorKind::Timeout)?,
+            None => self.query(resolver, name).await,
+        }
+    }
+
+    /// Send the queries for a single lookup of `name`.
+    async fn query(
+        &self,
+        resolver: &TokioAsyncResolver,
+        name: &str,
+    ) -> Result<SocketAddrs, ResolveError> {
+        let (mut ips, ipv6_first, valid_until): (Vec<_>, _, _) =
+            if let Some(client_subnet) = &self.client_subnet {
+                let (ips, valid_until) = client_subnet.lookup(name).await?;
+
+                (ips, true, valid_until)
+            } else if self.race_queries {
+                race(resolver, name, self.ip_strategy).await?
+            } else {
+                let lookup = resolver.lookup_ip(name).await?;
+
+                (lookup.iter().collect(), true, Some(lookup.valid_until()))
+            };
+
+        if self.block_private_ips {
+            ips.retain(|ip| !is_private(*ip));
+
+            if ips.is_empty() {
+                return Err(ResolveError::from(format!(
+                    "all addresses of {name} are private or reserved"
+                )));
+            }
+        }
+
+        Ok(SocketAddrs::new(ips, self.order(ipv6_first)).with_valid_until(valid_until))
+    }
+}
+
+/// Whether a lookup that failed with `error` is worth retrying.
+fn is_transient(error: &ResolveError) -> bool {
+    matches!(
+        error.kind(),
+        ResolveErrorKind::Timeout | ResolveErrorKind::NoConnections
+    )
+}
+
+/// Whether `ip` is in a private, loopback, link-local or otherwise reserved
+/// range that should not be reachable through public DNS names.
+fn is_private(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_ipv4(ip),
+        IpAddr::V6(ip) => is_private_ipv6(ip) || embedded_ipv4(ip).is_some_and(is_private_ipv4),
+    }
+}
+
+/// The IPv4 address embedded in `ip` if it is an IPv4-mapped, IPv4-compatible,
+/// NAT64 or 6to4 address, which all reach the embedded IPv4 address.
+fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    let octets = ip.octets();
+
+    match ip.segments() {
+        // ::/96, IPv4-compatible, ::ffff:0:0/96, IPv4-mapped, and
+        // 64:ff9b::/96, NAT64 addresses
+        [0, 0, 0, 0, 0, 0 | 0xffff, ..] | [0x64, 0xff9b, 0, 0, 0, 0, ..] => Some(Ipv4Addr::new(
+            octets[12], octets[13], octets[14], octets[15],
+        )),
+        // 2002::/16, 6to4, with the IPv4 address in bits 16 to 48
+        [0x2002, ..] => Some(Ipv4Addr::new(octets[2], octets[3], octets[4], octets[5])),
+        _ => None,
+    }
+}
+
+fn is_private_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // 0.0.0.0/8, "this network"
+        || a == 0
+        // 100.64.0.0/10, shared address space for carrier-grade NAT
+        || (a == 100 && (b & 0xc0) == 64)
+        // 192.0.0.0/24, IETF protocol assignments
+        || (a == 192 && b == 0 && c == 0)
+        // 198.18.0.0/15, benchmarking
+        || (a == 198 && (b & 0xfe) == 18)
+        // 240.0.0.0/4, reserved for future use
+        || a >= 240
+}
+
+fn is_private_ipv6(ip: Ipv6Addr) -> bool {
+    let [first, second, ..] = ip.segments();
+
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // fc00::/7, unique local addresses
+        || (first & 0xfe00) == 0xfc00
+        // fe80::/10, link-local addresses
+        || (first & 0xffc0) == 0xfe80
+        // 2001:db8::/32, documentation
+        || (first == 0x2001 && second == 0x0db8)
+}
+
+/// How long to wait for the slower address family once the faster one was
+/// answered, like the Resolution Delay of RFC 8305.
+const RESOLUTION_DELAY: Duration = Duration::from_millis(50);
+
+/// The addresses of a single address family and when their records expire.
+type FamilyLookup = Result<(Vec<IpAddr>, Instant), ResolveError>;
+
+/// Look up the IPv4 and IPv6 addresses of `name` concurrently, as far as
+/// `ip_strategy` allows.
+///
+/// With [`LookupIpStrategy::Ipv4AndIpv6`], the addresses of whichever lookup
+/// finishes first come first, followed by the addresses of the slower lookup
+/// if it finishes within the [`RESOLUTION_DELAY`]. With the `*then*`
+/// strategies, the second address family is only used if the first one has
+/// no addresses. Alongside the addresses, this returns whether the IPv6
+/// addresses came first and when the records expire.
+async fn race(
+    resolver: &TokioAsyncResolver,
+    name: &str,
+    ip_strategy: LookupIpStrategy,
+) -> Result<(Vec<IpAddr>, bool, Option<Instant>), ResolveError> {
+    // IP addresses are not resolved by the record specific lookups
+    if let Ok(ip) = name.parse::<IpAddr>() {
+        return Ok((vec![ip], ip.is_ipv6(), None));
+    }
+
+    let ipv4 = async {
+        let lookup = resolver.ipv4_lookup(name).await?;
+        let ips = lookup.iter().map(|a| IpAddr::V4(a.0)).collect::<Vec<_>>();
+
+        Ok::<_, ResolveError>((ips, lookup.valid_until()))
+    };
+    let ipv6 = async {
+        let lookup = resolver.ipv6_lookup(name).await?;
+        let ips = lookup
+            .iter()
+            .map(|aaaa| IpAddr::V6(aaaa.0))
+            .collect::<Vec<_>>();
+
+        Ok::<_, ResolveError>((ips, lookup.valid_until()))
+    };
+
+    match ip_strategy {
+        LookupIpStrategy::Ipv4Only => ipv4
+            .await
+            .map(|(ips, valid_until)| (ips, false, Some(valid_until))),
+        LookupIpStrategy::Ipv6Only => ipv6
+            .await
+            .map(|(ips, valid_until)| (ips, true, Some(valid_until))),
+        LookupIpStrategy::Ipv4AndIpv6 => first_wins(ipv4, ipv6).await,
+        LookupIpStrategy::Ipv4thenIpv6 => with_fallback(ipv4, ipv6, false).await,
+        LookupIpStrategy::Ipv6thenIpv4 => with_fallback(ipv6, ipv4, true).await,
+    }
+}
+
+/// Return the addresses of whichever of `ipv4` and `ipv6` finishes first,
+/// followed by those of the other one if it finishes within the
+/// [`RESOLUTION_DELAY`].
+async fn first_wins(
+    ipv4: impl Future<Output = FamilyLookup>,
+    ipv6: impl Future<Output = FamilyLookup>,
+) -> Result<(Vec<IpAddr>, bool, Option<Instant>), ResolveError> {
+    tokio::pin!(ipv4, ipv6);
+
+    let (first, ipv6_first) = tokio::select! {
+        result = &mut ipv4 => (result, false),
+        result = &mut ipv6 => (result, true),
+    };
+    let slower = async {
+        if ipv6_first {
+            ipv4.await
+        } else {
+            ipv6.await
+        }
+    };
+
+    match first {
+        Ok((mut ips, valid_until)) => {
+            // A hanging query of the slower family must not delay the lookup
+            match tokio::time::timeout(RESOLUTION_DELAY, slower).await {
+                Ok(Ok((slower, slower_valid_until))) => {
+                    ips.extend(slower);
+
+                    Ok((ips, ipv6_first, Some(valid_until.min(slower_valid_until))))
+                }
+                _ => Ok((ips, ipv6_first, Some(valid_until))),
+            }
+        }
+        Err(e) => slower
+            .await
+            .map(|(ips, valid_until)| (ips, !ipv6_first, Some(valid_until)))
+            .map_err(|_| e),
+    }
+}
+
+/// Return the addresses of `preferred`, or those of `fallback` if it has
+/// none. Both are looked up concurrently, so that falling back does not take
+/// another round trip.
+async fn with_fallback(
+    preferred: impl Future<Output = FamilyLookup>,
+    fallback: impl Future<Output = FamilyLookup>,
+    preferred_is_ipv6: bool,
+) -> Result<(Vec<IpAddr>, bool, Option<Instant>), ResolveError> {
+    tokio::pin!(preferred, fallback);
+
+    let (preferred, fallback) = tokio::select! {
+        result = &mut preferred => match result {
+            Ok(result) => (Ok(result), None),
+            Err(e) => (Err(e), Some(fallback.await)),
+        },
+        result = &mut fallback => (preferred.await, Some(result)),
+    };
+
+    match (preferred, fallback) {
+        (Ok((ips, valid_until)), _) => Ok((ips, preferred_is_ipv6, Some(valid_until))),
+        (Err(_), Some(Ok((ips, valid_until)))) => Ok((ips, !preferred_is_ipv6, Some(valid_until))),
+        (Err(e), _) => Err(e),
+    }
+}
@@ -0,0 +1,82 @@
+//! Prometheus metrics for lookups.
+
+use std::{fmt, time::Duration};
+
+use prometheus::{Histogram, HistogramOpts, IntCounterVec, Opts, Registry};
+
+use crate::{resolver::error::ResolveErrorKind, ResolveError};
+
+/// Prometheus metrics of the lookups of one or more [`TrustDnsResolver`]s.
+///
+/// The metrics are registered once with [`TrustDnsMetrics::new`] and can be
+/// passed to any number of resolvers with
+/// [`TrustDnsResolverBuilder::metrics`], cloning them is cheap. Two metrics
+/// are recorded:
+///
+/// - `trust_dns_lookup_duration_seconds`, a histogram of the duration of
+///   lookups, including retries.
+/// - `trust_dns_lookup_errors_total`, a counter of failed lookups with the
+///   kind of error as the `kind` label.
+///
+/// [`TrustDnsResolver`]: crate::TrustDnsResolver
+/// [`TrustDnsResolverBuilder::metrics`]: crate::TrustDnsResolverBuilder::metrics
+#[derive(Clone)]
+pub struct TrustDnsMetrics {
+    duration: Histogram,
+    errors: IntCounterVec,
+}
+
+impl TrustDnsMetrics {
+    /// Create the metrics and register them with `registry`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if metrics with the same names are already registered
+    /// with `registry`.
+    pub fn new(registry: &Registry) -> Result<Self, prometheus::Error> {
+        let duration = Histogram::with_opts(HistogramOpts::new(
+            "trust_dns_lookup_duration_seconds",
+            "Duration of DNS lookups in seconds.",
+        ))?;
+        let errors = IntCounterVec::new(
+            Opts::new(
+                "trust_dns_lookup_errors_total",
+                "Number of failed DNS lookups.",
+            ),
+            &["kind"],
+        )?;
+
+        registry.register(Box::new(duration.clone()))?;
+        registry.register(Box::new(errors.clone()))?;
+
+        Ok(Self { duration, errors })
+    }
+
+    /// Record a lookup that took `elapsed` and failed with `error`, if any.
+    pub(crate) fn record(&self, elapsed: Duration, error: Option<&ResolveError>) {
+        self.duration.observe(elapsed.as_secs_f64());
+
+        if let Some(error) = error {
+            self.errors.with_label_values(&[error_kind(error)]).inc();
+        }
+    }
+}
+
+impl fmt::Debug for TrustDnsMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TrustDnsMetrics").finish_non_exhaustive()
+    }
+}
+
+/// The value of the `kind` label for `error`.
+fn error_kind(error: &ResolveError) -> &'static str {
+    match error.kind() {
+        ResolveErrorKind::NoRecordsFound { .. } => "no_records_found",
+        ResolveErrorKind::Timeout => "timeout",
+        ResolveErrorKind::NoConnections => "no_connections",
+        ResolveErrorKind::Io(_) => "io",
+        ResolveErrorKind::Proto(_) => "proto",
+        ResolveErrorKind::Message(_) | ResolveErrorKind::Msg(_) => "message",
+        _ => "other",
+    }
+}
@@ -0,0 +1,106 @@
+//! A resolver that answers lookups from a fixed set of records, for tests.
+
+use std::{
+    collections::HashMap,
+    future::{self, Ready},
+    net::IpAddr,
+    sync::Arc,
+};
+
+use hyper::client::HttpConnector;
+
+use crate::{
+    addrs::AddrOrder,
+    service::{impl_service, Resolve},
+    DnsConnectError, ResolveError, SocketAddrs,
+};
+
+/// A resolver that answers lookups from a fixed map of names to addresses
+/// instead of querying DNS.
+///
+/// It implements the same [`Service`](hyper::service::Service) interface as
+/// [`TrustDnsResolver`](crate::TrustDnsResolver), so it can replace it in
+/// tests. Lookups of names that are not in the map fail.
+#[derive(Debug, Clone, Default)]
+pub struct TrustDnsMockResolver {
+    records: Arc<HashMap<String, Vec<IpAddr>>>,
+}
+
+impl TrustDnsMockResolver {
+    /// Create a new [`TrustDnsMockResolver`] that resolves the names in
+    /// `records` to the given addresses. The addresses are returned in the
+    /// order they are listed in.
+    #[must_use]
+    pub fn new(records: HashMap<String, Vec<IpAddr>>) -> Self {
+        Self {
+            records: Arc::new(records),
+        }
+    }
+
+    /// Create a new [`LocalHttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> LocalHttpConnector {
+        HttpConnector::new_with_resolver(self)
+    }
+
+    /// Create a new [`LocalHttpsConnector`] with this resolver and a custom
+    /// rustls [`ClientConfig`](rustls::ClientConfig), for example with the
+    /// root certificate of a test server.
+    ///
+    /// Unlike the connectors of [`TrustDnsResolver`](crate::TrustDnsResolver),
+    /// this always allows plain HTTP as well, regardless of the `https-only`
+    /// feature. The ALPN protocols of `tls_config` are replaced with the ones
+    /// enabled by the `rustls-http1` and `rustls-http2` features.
+    #[cfg(any(feature = "rustls-native", feature = "rustls-webpki"))]
+    #[must_use]
+    pub fn into_https_connector_with_config(
+        self,
+        mut tls_config: rustls::ClientConfig,
+    ) -> LocalHttpsConnector {
+        let mut http_connector = self.into_http_connector();
+        http_connector.enforce_http(false);
+
+        tls_config.alpn_protocols.clear();
+
+        let builder = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(tls_config)
+            .https_or_http();
+
+        #[cfg(feature = "rustls-http1")]
+        let builder = builder.enable_http1();
+
+        #[cfg(feature = "rustls-http2")]
+        let builder = builder.enable_http2();
+
+        builder.wrap_connector(http_connector)
+    }
+}
+
+/// Another name for [`TrustDnsMockResolver`], for code that maps names to
+/// local test servers.
+pub type LocalResolver = TrustDnsMockResolver;
+
+/// A [`HttpConnector`] that uses the [`TrustDnsMockResolver`].
+pub type LocalHttpConnector = HttpConnector<TrustDnsMockResolver>;
+
+/// A [`hyper_rustls::HttpsConnector`] that uses a [`LocalHttpConnector`].
+#[cfg(any(feature = "rustls-native", feature = "rustls-webpki"))]
+pub type LocalHttpsConnector = hyper_rustls::HttpsConnector<LocalHttpConnector>;
+
+impl Resolve for TrustDnsMockResolver {
+    type Future = Ready<Result<SocketAddrs, DnsConnectError>>;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        let result = match self.records.get(name) {
+            Some(ips) => Ok(SocketAddrs::new(ips.iter().copied(), AddrOrder::Unchanged)),
+            None => Err(ResolveError::from(format!("no mock records for {name}")).into()),
+        };
+
+        future::ready(result)
+    }
+}
+
+impl_service!(
+    TrustDnsMockResolver,
+    Ready<Result<SocketAddrs, DnsConnectError>>
+);
@@ -0,0 +1,144 @@
+//! Querying several resolvers at once.
+
+use std::net::IpAddr;
+
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+use tokio::task::JoinSet;
+
+use crate::{
+    addrs::AddrOrder,
+    service::{impl_service, Resolve},
+    ResolveError, SocketAddrs, TrustDnsResolver,
+};
+
+/// A resolver that queries a list of [`TrustDnsResolver`]s concurrently and
+/// merges their results.
+///
+/// Each lookup is sent to all resolvers in parallel. As soon as
+/// `min_success` of them succeeded, the remaining lookups are cancelled and
+/// the addresses of the successful lookups are combined in the order of the
+/// resolvers, without duplicates. The combined addresses are then ordered
+/// according to the happy eyeballs and IPv4 preference settings of the first
+/// resolver. Unlike a [`FallbackTrustDnsResolver`], a single misbehaving
+/// resolver does not delay the lookup until it fails.
+///
+/// [`FallbackTrustDnsResolver`]: crate::FallbackTrustDnsResolver
+#[derive(Debug, Clone)]
+pub struct MultiProviderResolver {
+    resolvers: Vec<TrustDnsResolver>,
+    min_success: usize,
+}
+
+impl MultiProviderResolver {
+    /// Create a new [`MultiProviderResolver`] that queries all of
+    /// `resolvers`. Lookups fail if the list is empty.
+    #[must_use]
+    pub fn new(resolvers: Vec<TrustDnsResolver>) -> Self {
+        Self {
+            resolvers,
+            min_success: 1,
+        }
+    }
+
+    /// Only succeed if at least `min_success` of the resolvers returned a
+    /// result. Defaults to 1.
+    #[must_use]
+    pub fn min_success(mut self, min_success: usize) -> Self {
+        self.min_success = min_success;
+
+        self
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+}
+
+impl Resolve for MultiProviderResolver {
+    type Future = <TrustDnsResolver as Service<Name>>::Future;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        let name = name.to_owned();
+
+        // Dropping the set cancels the lookups that have not finished yet
+        let mut lookups = JoinSet::new();
+        for (index, resolver) in self.resolvers.iter().enumerate() {
+            let lookup = resolver.resolve(name.clone());
+            lookups.spawn(async move { (index, lookup.await) });
+        }
+
+        let settings = self
+            .resolvers
+            .first()
+            .map(|resolver| resolver.settings.clone());
+        let min_success = self.min_success.max(1);
+
+        Box::pin(async move {
+            let mut results: Vec<_> = (0..lookups.len()).map(|_| None).collect();
+            let mut successes = 0;
+            let mut first_error = None;
+
+            while successes < min_success && successes + lookups.len() >= min_success {
+                let Some(joined) = lookups.join_next().await else {
+                    break;
+                };
+
+                match joined {
+                    Ok((index, result)) => {
+                        successes += usize::from(result.is_ok());
+                        results[index] = Some(result);
+                    }
+                    Err(e) => {
+                        first_error.get_or_insert_with(|| ResolveError::from(e.to_string()).into());
+                    }
+                }
+            }
+
+            let mut ips = Vec::new();
+            let mut valid_until = None;
+
+            for result in results.into_iter().flatten() {
+                match result {
+                    Ok(addrs) => {
+                        valid_until = match (valid_until, addrs.valid_until()) {
+                            (Some(a), Some(b)) => Some(std::cmp::min(a, b)),
+                            (a, b) => a.or(b),
+                        };
+
+                        for addr in addrs {
+                            if !ips.contains(&addr.ip()) {
+                                ips.push(addr.ip());
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        first_error.get_or_insert(e);
+                    }
+                }
+            }
+
+            if successes < min_success {
+                return Err(first_error.unwrap_or_else(|| {
+                    ResolveError::from(format!(
+                        "only {successes} of at least {min_success} lookups of {name} succeeded"
+                    ))
+                    .into()
+                }));
+            }
+
+            // Merging can mix up the address families, so order them again
+            let order = settings.map_or(AddrOrder::Unchanged, |settings| {
+                settings.order(ips.first().is_none_or(IpAddr::is_ipv6))
+            });
+
+            Ok(SocketAddrs::new(ips, order).with_valid_until(valid_until))
+        })
+    }
+}
+
+impl_service!(MultiProviderResolver);
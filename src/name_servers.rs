@@ -0,0 +1,73 @@
+//! Parsing nameservers from configuration sources.
+
+use std::net::{IpAddr, SocketAddr};
+
+use crate::resolver::config::{NameServerConfig, Protocol};
+
+/// An error in the nameservers of a configuration source.
+pub(crate) enum NameServerError {
+    /// There are no nameservers.
+    Missing,
+    /// A nameserver is not an IP address with an optional port.
+    Invalid(String),
+    /// The protocol is not known, or the feature for it is not enabled.
+    UnsupportedProtocol(String),
+    /// The protocol requires a TLS name, but there is none.
+    MissingTlsName,
+}
+
+/// Create the [`NameServerConfig`]s for `servers`, which are IP addresses
+/// with an optional port, queried with `protocol`.
+///
+/// The protocol is one of `udp`, `tcp`, `tls` and `https`. UDP lookups fall
+/// back to TCP for truncated responses, like
+/// `TrustDnsResolver::with_nameservers`.
+pub(crate) fn parse<'a>(
+    servers: impl IntoIterator<Item = &'a str>,
+    protocol: &str,
+    tls_name: Option<&str>,
+) -> Result<Vec<NameServerConfig>, NameServerError> {
+    let (protocols, default_port, encrypted): (&[Protocol], u16, bool) =
+        match protocol.to_lowercase().as_str() {
+            "udp" => (&[Protocol::Udp, Protocol::Tcp], 53, false),
+            "tcp" => (&[Protocol::Tcp], 53, false),
+            #[cfg(any(
+                feature = "dns-over-rustls",
+                feature = "dns-over-native-tls",
+                feature = "dns-over-openssl"
+            ))]
+            "tls" => (&[Protocol::Tls], 853, true),
+            #[cfg(feature = "dns-over-https-rustls")]
+            "https" => (&[Protocol::Https], 443, true),
+            _ => return Err(NameServerError::UnsupportedProtocol(protocol.to_owned())),
+        };
+
+    if encrypted && tls_name.is_none() {
+        return Err(NameServerError::MissingTlsName);
+    }
+
+    let mut name_servers = Vec::new();
+
+    for server in servers.into_iter().map(str::trim).filter(|s| !s.is_empty()) {
+        let addr = match server.parse::<SocketAddr>() {
+            Ok(addr) => addr,
+            Err(_) => server
+                .parse::<IpAddr>()
+                .map(|ip| SocketAddr::new(ip, default_port))
+                .map_err(|_| NameServerError::Invalid(server.to_owned()))?,
+        };
+
+        for &protocol in protocols {
+            let mut name_server = NameServerConfig::new(addr, protocol);
+            name_server.tls_dns_name = tls_name.map(str::to_owned);
+
+            name_servers.push(name_server);
+        }
+    }
+
+    if name_servers.is_empty() {
+        return Err(NameServerError::Missing);
+    }
+
+    Ok(name_servers)
+}
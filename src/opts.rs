@@ -0,0 +1,84 @@
+//! Presets of resolver options.
+
+use std::time::Duration;
+
+use crate::resolver::config::ResolverOpts;
+
+/// Named presets of [`ResolverOpts`] for common use cases.
+///
+/// The presets start out with the default options and can be converted into
+/// [`ResolverOpts`] with [`From`] or [`TrustDnsResolverOpts::into_inner`] to
+/// adjust them further.
+///
+/// # Example
+///
+/// ```
+/// use hyper_trust_dns::{resolver::config::ResolverConfig, TrustDnsResolverOpts};
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// let resolver = hyper_trust_dns::TrustDnsResolver::builder()
+///     .config(ResolverConfig::cloudflare())
+///     .options(TrustDnsResolverOpts::fast().into())
+///     .build()
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TrustDnsResolverOpts(ResolverOpts);
+
+impl TrustDnsResolverOpts {
+    /// Options for latency-sensitive applications: requests time out after
+    /// one second and are only attempted once.
+    #[must_use]
+    pub fn fast() -> Self {
+        let mut options = ResolverOpts::default();
+        options.timeout = Duration::from_secs(1);
+        options.attempts = 1;
+
+        Self(options)
+    }
+
+    /// Options for unreliable networks: requests time out after 10 seconds,
+    /// are attempted 5 times and are sent to two nameservers concurrently.
+    #[must_use]
+    pub fn resilient() -> Self {
+        let mut options = ResolverOpts::default();
+        options.timeout = Duration::from_secs(10);
+        options.attempts = 5;
+        options.num_concurrent_reqs = 2;
+
+        Self(options)
+    }
+
+    /// Options that validate all responses with DNSSEC, see
+    /// [`TrustDnsResolverBuilder::dnssec_validation`].
+    ///
+    /// [`TrustDnsResolverBuilder::dnssec_validation`]: crate::TrustDnsResolverBuilder::dnssec_validation
+    #[cfg(any(feature = "dnssec-openssl", feature = "dnssec-ring"))]
+    #[must_use]
+    pub fn strict_dnssec() -> Self {
+        let mut options = ResolverOpts::default();
+        options.validate = true;
+
+        Self(options)
+    }
+
+    /// Get the [`ResolverOpts`] of this preset.
+    #[must_use]
+    pub fn into_inner(self) -> ResolverOpts {
+        self.0
+    }
+}
+
+impl From<TrustDnsResolverOpts> for ResolverOpts {
+    fn from(options: TrustDnsResolverOpts) -> Self {
+        options.0
+    }
+}
+
+impl AsRef<ResolverOpts> for TrustDnsResolverOpts {
+    fn as_ref(&self) -> &ResolverOpts {
+        &self.0
+    }
+}
@@ -0,0 +1,42 @@
+//! OpenTelemetry spans for lookups.
+
+use opentelemetry::{
+    global,
+    trace::{Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
+
+use crate::{ResolveError, SocketAddrs};
+
+/// Start a `dns.lookup` span for a lookup of `name` as a child of the current
+/// span and return the context that should be attached to the lookup.
+pub(crate) fn start_span(name: &str) -> Context {
+    let parent_cx = Context::current();
+    let tracer = global::tracer("hyper-trust-dns");
+
+    let span = tracer
+        .span_builder("dns.lookup")
+        .with_attributes([
+            KeyValue::new("dns.question.name", name.to_owned()),
+            KeyValue::new("net.peer.name", name.to_owned()),
+        ])
+        .start_with_context(&tracer, &parent_cx);
+
+    parent_cx.with_span(span)
+}
+
+/// Record `result` on the span of the current context and end it.
+pub(crate) fn end_span(result: &Result<SocketAddrs, ResolveError>) {
+    let cx = Context::current();
+    let span = cx.span();
+
+    match result {
+        Ok(addrs) => span.set_attribute(KeyValue::new(
+            "dns.answers_count",
+            i64::try_from(addrs.len()).unwrap_or(i64::MAX),
+        )),
+        Err(e) => span.set_status(Status::error(e.to_string())),
+    }
+
+    span.end();
+}
@@ -0,0 +1,120 @@
+//! Managing resolvers per key.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    resolver::config::{ResolverConfig, ResolverOpts},
+    TrustDnsResolver,
+};
+
+/// A set of [`TrustDnsResolver`]s keyed by a user-defined type, for example
+/// one resolver per tenant or region.
+///
+/// Clones share the same resolvers.
+pub struct ResolverPool<K> {
+    resolvers: Arc<Mutex<HashMap<K, TrustDnsResolver>>>,
+}
+
+impl<K: Eq + Hash + Clone> ResolverPool<K> {
+    /// Create a new, empty [`ResolverPool`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            resolvers: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Get the resolver for `key`, creating it with `config` and `options` if
+    /// there is none yet. The config and options are ignored if the resolver
+    /// already exists. This must be run inside a Tokio runtime context.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool lock was poisoned.
+    pub fn get_or_create(
+        &self,
+        key: K,
+        config: ResolverConfig,
+        options: ResolverOpts,
+    ) -> TrustDnsResolver {
+        self.resolvers
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| TrustDnsResolver::with_config_and_options(config, options))
+            .clone()
+    }
+
+    /// Get the resolver for `key`, if there is one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool lock was poisoned.
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<TrustDnsResolver> {
+        self.resolvers.lock().unwrap().get(key).cloned()
+    }
+
+    /// Remove the resolver for `key` from the pool and return it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool lock was poisoned.
+    pub fn remove(&self, key: &K) -> Option<TrustDnsResolver> {
+        self.resolvers.lock().unwrap().remove(key)
+    }
+
+    /// Get the keys of all resolvers in the pool.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool lock was poisoned.
+    #[must_use]
+    pub fn keys(&self) -> Vec<K> {
+        self.resolvers.lock().unwrap().keys().cloned().collect()
+    }
+
+    /// Remove all resolvers from the pool.
+    ///
+    /// The resolvers shut down their connections to the nameservers once the
+    /// last clone of them is dropped, so resolvers that are still in use
+    /// elsewhere keep working.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pool lock was poisoned.
+    pub fn shutdown_all(&self) {
+        self.resolvers.lock().unwrap().clear();
+    }
+}
+
+impl<K: Eq + Hash + Clone> Default for ResolverPool<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Clone for ResolverPool<K> {
+    fn clone(&self) -> Self {
+        Self {
+            resolvers: self.resolvers.clone(),
+        }
+    }
+}
+
+impl<K: fmt::Debug> fmt::Debug for ResolverPool<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.resolvers.lock() {
+            Ok(resolvers) => f
+                .debug_struct("ResolverPool")
+                .field("keys", &resolvers.keys().collect::<Vec<_>>())
+                .finish(),
+            Err(_) => f.debug_struct("ResolverPool").finish_non_exhaustive(),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Refreshing lookup results before they expire.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+
+use crate::{
+    service::{impl_service, Resolve},
+    SocketAddrs, TrustDnsResolver,
+};
+
+/// A resolver that refreshes the results of a [`TrustDnsResolver`] in the
+/// background before they expire, so that busy names never miss the cache.
+///
+/// Results are cached until the TTL of their records runs out. Once less than
+/// the refresh threshold of the TTL remains, the next lookup of the name still
+/// returns the cached addresses, but also looks the name up again in a
+/// background task. Names that are not looked up anymore simply expire.
+///
+/// The cache of the inner resolver would answer the refreshes with the same
+/// records, so it should be disabled with
+/// [`TrustDnsResolverBuilder::cache_size`] set to 0.
+///
+/// Clones share the same cache.
+///
+/// [`TrustDnsResolverBuilder::cache_size`]: crate::TrustDnsResolverBuilder::cache_size
+#[derive(Debug, Clone)]
+pub struct PrefetchingResolver {
+    inner: TrustDnsResolver,
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+    threshold: f64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    addrs: SocketAddrs,
+    resolved: Instant,
+    expires: Instant,
+    /// Whether a background lookup is refreshing this entry.
+    refreshing: bool,
+}
+
+impl PrefetchingResolver {
+    /// Create a new [`PrefetchingResolver`] around `inner`.
+    ///
+    /// By default, names are refreshed once 10% of their TTL remain.
+    #[must_use]
+    pub fn new(inner: TrustDnsResolver) -> Self {
+        Self {
+            inner,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            threshold: 0.1,
+        }
+    }
+
+    /// Refresh names once less than `threshold` (between 0 and 1) of their
+    /// TTL remain.
+    #[must_use]
+    pub fn refresh_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold.clamp(0.0, 1.0);
+
+        self
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+
+    /// Look `name` up again in a background task.
+    fn refresh(&self, name: String) {
+        let entries = self.entries.clone();
+        let lookup = self.inner.resolve(name.clone());
+
+        tokio::spawn(async move {
+            match lookup.await {
+                Ok(addrs) => insert(&entries, name, &addrs),
+                Err(_) => {
+                    if let Some(entry) = entries.lock().unwrap().get_mut(&name) {
+                        entry.refreshing = false;
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Cache the addresses resolved for `name` until their records expire and
+/// remove expired entries. Addresses without a TTL, like those of IP
+/// addresses, are not cached.
+fn insert(entries: &Mutex<HashMap<String, Entry>>, name: String, addrs: &SocketAddrs) {
+    let now = Instant::now();
+    let Some(expires) = addrs.valid_until().filter(|&expires| expires > now) else {
+        return;
+    };
+
+    let mut entries = entries.lock().unwrap();
+    entries.retain(|_, entry| entry.expires > now);
+    entries.insert(
+        name,
+        Entry {
+            addrs: addrs.clone(),
+            resolved: now,
+            expires,
+            refreshing: false,
+        },
+    );
+}
+
+impl Resolve for PrefetchingResolver {
+    type Future = <TrustDnsResolver as Service<Name>>::Future;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        let name = name.to_owned();
+
+        let now = Instant::now();
+        let cached = {
+            let mut entries = self.entries.lock().unwrap();
+
+            match entries.get_mut(&name) {
+                Some(entry) if entry.expires > now => {
+                    let ttl = entry.expires - entry.resolved;
+                    let refresh =
+                        !entry.refreshing && entry.expires - now < ttl.mul_f64(self.threshold);
+                    entry.refreshing |= refresh;
+
+                    Some((entry.addrs.clone(), refresh))
+                }
+                _ => None,
+            }
+        };
+
+        if let Some((addrs, refresh)) = cached {
+            if refresh {
+                self.refresh(name);
+            }
+
+            return Box::pin(async move { Ok(addrs) });
+        }
+
+        let entries = self.entries.clone();
+        let lookup = self.inner.resolve(name.clone());
+
+        Box::pin(async move {
+            let addrs = lookup.await?;
+            insert(&entries, name, &addrs);
+
+            Ok(addrs)
+        })
+    }
+}
+
+impl_service!(PrefetchingResolver);
@@ -0,0 +1,138 @@
+//! Configurations for DNS providers that the resolver backend has no presets
+//! for.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
+
+/// IP addresses of the OpenDNS (Cisco Umbrella) DNS service.
+const OPENDNS_IPS: &[IpAddr] = &[
+    IpAddr::V4(Ipv4Addr::new(208, 67, 222, 222)),
+    IpAddr::V4(Ipv4Addr::new(208, 67, 220, 220)),
+    IpAddr::V6(Ipv6Addr::new(0x2620, 0x119, 0x35, 0, 0, 0, 0, 0x35)),
+    IpAddr::V6(Ipv6Addr::new(0x2620, 0x119, 0x53, 0, 0, 0, 0, 0x53)),
+];
+
+/// IP addresses of the AdGuard DNS service.
+const ADGUARD_IPS: &[IpAddr] = &[
+    IpAddr::V4(Ipv4Addr::new(94, 140, 14, 14)),
+    IpAddr::V4(Ipv4Addr::new(94, 140, 15, 15)),
+    IpAddr::V6(Ipv6Addr::new(0x2a10, 0x50c0, 0, 0, 0, 0, 0xad1, 0xff)),
+    IpAddr::V6(Ipv6Addr::new(0x2a10, 0x50c0, 0, 0, 0, 0, 0xad2, 0xff)),
+];
+
+/// A DNS provider to create a [`TrustDnsResolver`] for with
+/// [`TrustDnsResolver::from_provider`].
+///
+/// The well-known providers are queried via UDP, falling back to TCP, with the
+/// default options.
+///
+/// [`TrustDnsResolver`]: crate::TrustDnsResolver
+/// [`TrustDnsResolver::from_provider`]: crate::TrustDnsResolver::from_provider
+// Providers are only used once to create a resolver, so boxing the custom
+// configuration is not worth the less convenient construction
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum DnsProvider {
+    /// The Google Public DNS nameservers.
+    Google,
+    /// The Cloudflare nameservers.
+    Cloudflare,
+    /// The Quad9 nameservers.
+    Quad9,
+    /// The OpenDNS (Cisco Umbrella) nameservers.
+    OpenDns,
+    /// The AdGuard DNS nameservers.
+    AdGuard,
+    /// A custom configuration.
+    Custom {
+        /// The configuration of the nameservers.
+        config: ResolverConfig,
+        /// The options of the resolver.
+        options: ResolverOpts,
+    },
+}
+
+impl DnsProvider {
+    /// Get the [`ResolverConfig`] and [`ResolverOpts`] of this provider.
+    pub(crate) fn into_parts(self) -> (ResolverConfig, ResolverOpts) {
+        let config = match self {
+            Self::Google => ResolverConfig::google(),
+            Self::Cloudflare => ResolverConfig::cloudflare(),
+            Self::Quad9 => ResolverConfig::quad9(),
+            Self::OpenDns => opendns(),
+            Self::AdGuard => adguard(),
+            Self::Custom { config, options } => return (config, options),
+        };
+
+        (config, ResolverOpts::default())
+    }
+}
+
+/// Create a [`ResolverConfig`] from a group of nameservers.
+fn from_name_servers(name_servers: NameServerConfigGroup) -> ResolverConfig {
+    ResolverConfig::from_parts(None, vec![], name_servers)
+}
+
+/// Create a [`ResolverConfig`] that uses the OpenDNS nameservers.
+pub(crate) fn opendns() -> ResolverConfig {
+    from_name_servers(NameServerConfigGroup::from_ips_clear(OPENDNS_IPS, 53, true))
+}
+
+/// Create a [`ResolverConfig`] that uses the OpenDNS nameservers.
+/// This limits the registered connections to just HTTPS lookups.
+#[cfg(feature = "dns-over-https-rustls")]
+pub(crate) fn opendns_https() -> ResolverConfig {
+    from_name_servers(NameServerConfigGroup::from_ips_https(
+        OPENDNS_IPS,
+        443,
+        "doh.opendns.com".to_owned(),
+        true,
+    ))
+}
+
+/// Create a [`ResolverConfig`] that uses the AdGuard nameservers.
+pub(crate) fn adguard() -> ResolverConfig {
+    from_name_servers(NameServerConfigGroup::from_ips_clear(ADGUARD_IPS, 53, true))
+}
+
+/// Create a [`ResolverConfig`] that uses the AdGuard nameservers.
+/// This limits the registered connections to just HTTPS lookups.
+#[cfg(feature = "dns-over-https-rustls")]
+pub(crate) fn adguard_https() -> ResolverConfig {
+    from_name_servers(NameServerConfigGroup::from_ips_https(
+        ADGUARD_IPS,
+        443,
+        "dns.adguard.com".to_owned(),
+        true,
+    ))
+}
+
+/// Create a [`ResolverConfig`] that uses the AdGuard nameservers.
+/// This limits the registered connections to just TLS lookups.
+#[cfg(any(
+    feature = "dns-over-rustls",
+    feature = "dns-over-native-tls",
+    feature = "dns-over-openssl"
+))]
+pub(crate) fn adguard_tls() -> ResolverConfig {
+    from_name_servers(NameServerConfigGroup::from_ips_tls(
+        ADGUARD_IPS,
+        853,
+        "dns.adguard.com".to_owned(),
+        true,
+    ))
+}
+
+/// Create a [`ResolverConfig`] that uses the AdGuard nameservers.
+/// This limits the registered connections to just QUIC lookups.
+#[cfg(feature = "dns-over-quic")]
+pub(crate) fn adguard_quic() -> ResolverConfig {
+    from_name_servers(NameServerConfigGroup::from_ips_quic(
+        ADGUARD_IPS,
+        853,
+        "dns.adguard-dns.com".to_owned(),
+        true,
+    ))
+}
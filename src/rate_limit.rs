@@ -0,0 +1,168 @@
+//! Rate limiting lookups per name.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+use tokio::time::Instant;
+
+use crate::{
+    service::{impl_service, Resolve},
+    ResolveError, SocketAddrs, TrustDnsResolver,
+};
+
+/// A resolver that limits how often each name is looked up with a
+/// [`TrustDnsResolver`].
+///
+/// Lookups of a name are counted in a sliding window. Once a name was looked
+/// up `max_queries` times within the window, further lookups are answered
+/// with the most recent successful result of that name without querying the
+/// resolver, or fail immediately if there is none or its records expired.
+/// Results without an expiry are kept for one window.
+///
+/// Clones share the same counters.
+#[derive(Debug, Clone)]
+pub struct RateLimitedResolver {
+    inner: TrustDnsResolver,
+    max_queries: usize,
+    window: Duration,
+    names: Arc<Mutex<Names>>,
+}
+
+/// The recent lookups of all names.
+#[derive(Debug)]
+struct Names {
+    windows: HashMap<String, Window>,
+    /// When names that were not looked up recently are forgotten next, or
+    /// `None` if the window is too long for lookups to ever expire.
+    next_sweep: Option<Instant>,
+}
+
+/// The recent lookups of a single name.
+#[derive(Debug, Default)]
+struct Window {
+    queries: VecDeque<Instant>,
+    /// The most recent successful result and until when it may be served, or
+    /// `None` if it never expires.
+    last_result: Option<(SocketAddrs, Option<Instant>)>,
+}
+
+impl Window {
+    /// Forget about the lookups that are older than `window`.
+    fn expire(&mut self, now: Instant, window: Duration) {
+        while self
+            .queries
+            .front()
+            .is_some_and(|query| now.duration_since(*query) >= window)
+        {
+            self.queries.pop_front();
+        }
+
+        if self
+            .last_result
+            .as_ref()
+            .is_some_and(|(_, expires)| expires.is_some_and(|expires| now >= expires))
+        {
+            self.last_result = None;
+        }
+    }
+
+    /// Whether this window can be forgotten, after expiring it.
+    fn is_unused(&self) -> bool {
+        self.queries.is_empty() && self.last_result.is_none()
+    }
+}
+
+impl RateLimitedResolver {
+    /// Create a new [`RateLimitedResolver`] that allows at most
+    /// `max_queries` lookups of each name per `window`.
+    #[must_use]
+    pub fn new(inner: TrustDnsResolver, max_queries: usize, window: Duration) -> Self {
+        Self {
+            inner,
+            max_queries,
+            window,
+            names: Arc::new(Mutex::new(Names {
+                windows: HashMap::new(),
+                next_sweep: Instant::now().checked_add(window),
+            })),
+        }
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+}
+
+impl Resolve for RateLimitedResolver {
+    type Future = <TrustDnsResolver as Service<Name>>::Future;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        let name = name.to_owned();
+        let now = Instant::now();
+
+        {
+            let mut names = self.names.lock().unwrap();
+
+            // Forget about names that were not looked up recently, at most
+            // once per window so that lookups do not have to visit every name
+            if names.next_sweep.is_some_and(|next_sweep| now >= next_sweep) {
+                names.windows.retain(|_, window| {
+                    window.expire(now, self.window);
+
+                    !window.is_unused()
+                });
+                names.next_sweep = now.checked_add(self.window);
+            }
+
+            let window = names.windows.entry(name.clone()).or_default();
+            window.expire(now, self.window);
+
+            if window.queries.len() >= self.max_queries {
+                let result = window
+                    .last_result
+                    .clone()
+                    .map(|(addrs, _)| addrs)
+                    .ok_or_else(|| {
+                        ResolveError::from(format!("rate limit for looking up {name} exceeded"))
+                            .into()
+                    });
+
+                return Box::pin(async move { result });
+            }
+
+            window.queries.push_back(now);
+        }
+
+        let lookup = self.inner.resolve(name.clone());
+        let names = self.names.clone();
+        let window = self.window;
+
+        Box::pin(async move {
+            let result = lookup.await;
+
+            if let Ok(addrs) = &result {
+                let expires = match addrs.valid_until() {
+                    Some(valid_until) => Some(Instant::from_std(valid_until)),
+                    None => Instant::now().checked_add(window),
+                };
+
+                if let Some(entry) = names.lock().unwrap().windows.get_mut(&name) {
+                    entry.last_result = Some((addrs.clone(), expires));
+                }
+            }
+
+            result
+        })
+    }
+}
+
+impl_service!(RateLimitedResolver);
@@ -0,0 +1,96 @@
+//! Lookups of other record types than IP addresses.
+
+use std::net::IpAddr;
+
+use crate::{
+    resolver::{proto::rr::rdata::SRV, Name},
+    ResolveError, TrustDnsResolver,
+};
+
+/// A service record (SRV) as defined in RFC 2782.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrvRecord {
+    srv: SRV,
+}
+
+impl SrvRecord {
+    /// The priority of the target host, lower values are preferred.
+    #[must_use]
+    pub fn priority(&self) -> u16 {
+        self.srv.priority()
+    }
+
+    /// The relative weight of the target host among those with the same
+    /// priority.
+    #[must_use]
+    pub fn weight(&self) -> u16 {
+        self.srv.weight()
+    }
+
+    /// The port of the service on the target host.
+    #[must_use]
+    pub fn port(&self) -> u16 {
+        self.srv.port()
+    }
+
+    /// The domain name of the target host.
+    #[must_use]
+    pub fn target(&self) -> &Name {
+        self.srv.target()
+    }
+
+    /// Get the underlying [`SRV`] record data.
+    #[must_use]
+    pub fn into_inner(self) -> SRV {
+        self.srv
+    }
+}
+
+impl From<SRV> for SrvRecord {
+    fn from(srv: SRV) -> Self {
+        Self { srv }
+    }
+}
+
+impl TrustDnsResolver {
+    /// Look up the SRV records of `name`, for example
+    /// `_grpc._tcp.example.com`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lookup fails.
+    pub async fn lookup_srv(&self, name: &str) -> Result<Vec<SrvRecord>, ResolveError> {
+        let lookup = self.resolver.srv_lookup(name).await?;
+
+        Ok(lookup.iter().cloned().map(SrvRecord::from).collect())
+    }
+
+    /// Look up the TXT records of `name`.
+    ///
+    /// A TXT record can consist of multiple strings, which are concatenated
+    /// without a separator, as specified for SPF records by RFC 7208. Invalid
+    /// UTF-8 is replaced with U+FFFD.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lookup fails.
+    pub async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, ResolveError> {
+        let lookup = self.resolver.txt_lookup(name).await?;
+
+        Ok(lookup
+            .iter()
+            .map(|txt| String::from_utf8_lossy(&txt.txt_data().concat()).into_owned())
+            .collect())
+    }
+
+    /// Look up the host names of `addr` with its PTR records.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lookup fails.
+    pub async fn reverse_lookup(&self, addr: IpAddr) -> Result<Vec<Name>, ResolveError> {
+        let lookup = self.resolver.reverse_lookup(addr).await?;
+
+        Ok(lookup.iter().map(|ptr| ptr.0.clone()).collect())
+    }
+}
@@ -0,0 +1,75 @@
+//! Integration with the resolver hook of reqwest.
+
+use std::sync::Arc;
+
+use reqwest::{
+    dns::{Addrs, Name, Resolve, Resolving},
+    ClientBuilder,
+};
+
+use crate::TrustDnsResolver;
+
+impl Resolve for TrustDnsResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let lookup = Self::resolve(self, name.as_str().to_owned());
+
+        Box::pin(async move {
+            let addrs: Addrs = Box::new(lookup.await?);
+
+            Ok(addrs)
+        })
+    }
+}
+
+/// Extension methods for [`reqwest::ClientBuilder`] to resolve names with a
+/// [`TrustDnsResolver`].
+///
+/// reqwest manages its own connectors, so only the resolver is replaced. TLS
+/// is still configured through reqwest.
+pub trait ReqwestClientBuilderExt {
+    /// Resolve names with `resolver`.
+    #[must_use]
+    fn trust_dns_resolver(self, resolver: TrustDnsResolver) -> Self;
+
+    /// Resolve names with [`TrustDnsResolver::new`].
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    fn trust_dns(self) -> Self;
+
+    /// Resolve names with [`TrustDnsResolver::google`].
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    fn trust_dns_google(self) -> Self;
+
+    /// Resolve names with [`TrustDnsResolver::cloudflare`].
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    fn trust_dns_cloudflare(self) -> Self;
+
+    /// Resolve names with [`TrustDnsResolver::quad9`].
+    /// This must be run inside a Tokio runtime context.
+    #[must_use]
+    fn trust_dns_quad9(self) -> Self;
+}
+
+impl ReqwestClientBuilderExt for ClientBuilder {
+    fn trust_dns_resolver(self, resolver: TrustDnsResolver) -> Self {
+        self.dns_resolver(Arc::new(resolver))
+    }
+
+    fn trust_dns(self) -> Self {
+        self.trust_dns_resolver(TrustDnsResolver::new())
+    }
+
+    fn trust_dns_google(self) -> Self {
+        self.trust_dns_resolver(TrustDnsResolver::google())
+    }
+
+    fn trust_dns_cloudflare(self) -> Self {
+        self.trust_dns_resolver(TrustDnsResolver::cloudflare())
+    }
+
+    fn trust_dns_quad9(self) -> Self {
+        self.trust_dns_resolver(TrustDnsResolver::quad9())
+    }
+}
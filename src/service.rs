@@ -0,0 +1,88 @@
+//! Implementing the resolver services of both hyper versions.
+
+use std::{
+    future::Future,
+    task::{self, Poll},
+};
+
+use crate::{DnsConnectError, SocketAddrs};
+
+/// A resolver that looks up names given as strings.
+///
+/// [`impl_service`] implements the resolver services for the names of hyper
+/// 0.14 and, with the `hyper1` feature, hyper-util on top of this.
+pub(crate) trait Resolve {
+    /// The future returned by [`Resolve::resolve`].
+    type Future: Future<Output = Result<SocketAddrs, DnsConnectError>> + Send + 'static;
+
+    /// Wait until the resolver is ready to look up a name. Resolvers are
+    /// always ready by default.
+    fn poll_ready(&mut self, _cx: &mut task::Context<'_>) -> Poll<Result<(), DnsConnectError>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Look up the addresses of `name`.
+    fn resolve(&mut self, name: &str) -> Self::Future;
+}
+
+/// Implement `Service<Name>` for the names of both hyper versions on a
+/// resolver that implements [`Resolve`].
+///
+/// The services return the future of a [`TrustDnsResolver`] unless another
+/// one is given, which must be the [`Resolve::Future`] of the resolver. It
+/// cannot be referred to directly since [`Resolve`] is private.
+///
+/// [`TrustDnsResolver`]: crate::TrustDnsResolver
+macro_rules! impl_service {
+    ($resolver:ty) => {
+        $crate::service::impl_service!(
+            $resolver,
+            <$crate::TrustDnsResolver as ::hyper::service::Service<
+                ::hyper::client::connect::dns::Name,
+            >>::Future
+        );
+    };
+    ($resolver:ty, $future:ty) => {
+        impl ::hyper::service::Service<::hyper::client::connect::dns::Name> for $resolver {
+            type Response = $crate::SocketAddrs;
+            type Error = $crate::DnsConnectError;
+            type Future = $future;
+
+            fn poll_ready(
+                &mut self,
+                cx: &mut ::std::task::Context<'_>,
+            ) -> ::std::task::Poll<Result<(), Self::Error>> {
+                $crate::service::Resolve::poll_ready(self, cx)
+            }
+
+            fn call(&mut self, name: ::hyper::client::connect::dns::Name) -> Self::Future {
+                $crate::service::Resolve::resolve(self, name.as_str())
+            }
+        }
+
+        #[cfg(feature = "hyper1")]
+        impl ::hyper::service::Service<::hyper_util::client::legacy::connect::dns::Name>
+            for $resolver
+        {
+            type Response = $crate::SocketAddrs;
+            type Error = $crate::DnsConnectError;
+            type Future = $future;
+
+            fn poll_ready(
+                &mut self,
+                cx: &mut ::std::task::Context<'_>,
+            ) -> ::std::task::Poll<Result<(), Self::Error>> {
+                $crate::service::Resolve::poll_ready(self, cx)
+            }
+
+            fn call(
+                &mut self,
+                name: ::hyper_util::client::legacy::connect::dns::Name,
+            ) -> Self::Future {
+                $crate::service::Resolve::resolve(self, name.as_str())
+            }
+        }
+    };
+}
+
+pub(crate) use impl_service;
@@ -0,0 +1,66 @@
+//! Sharing a resolver between connectors.
+
+use std::{ops::Deref, sync::Arc};
+
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+
+use crate::{
+    service::{impl_service, Resolve},
+    TrustDnsResolver,
+};
+
+/// A [`TrustDnsResolver`] behind an [`Arc`], so that a resolver that is
+/// already shared can be passed to [`HttpConnector::new_with_resolver`].
+///
+/// [`Service`] cannot be implemented for `Arc<TrustDnsResolver>` itself,
+/// since neither the trait nor [`Arc`] are defined in this crate. Convert the
+/// [`Arc`] with [`From`] instead.
+#[derive(Debug, Clone)]
+pub struct SharedTrustDnsResolver(Arc<TrustDnsResolver>);
+
+impl SharedTrustDnsResolver {
+    /// Create a new [`SharedTrustDnsResolver`] that uses `resolver`.
+    #[must_use]
+    pub fn new(resolver: Arc<TrustDnsResolver>) -> Self {
+        Self(resolver)
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+
+    /// Get the shared resolver.
+    #[must_use]
+    pub fn into_inner(self) -> Arc<TrustDnsResolver> {
+        self.0
+    }
+}
+
+impl From<Arc<TrustDnsResolver>> for SharedTrustDnsResolver {
+    fn from(resolver: Arc<TrustDnsResolver>) -> Self {
+        Self(resolver)
+    }
+}
+
+impl Deref for SharedTrustDnsResolver {
+    type Target = TrustDnsResolver;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Resolve for SharedTrustDnsResolver {
+    type Future = <TrustDnsResolver as Service<Name>>::Future;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        self.0.resolve(name.to_owned())
+    }
+}
+
+impl_service!(SharedTrustDnsResolver);
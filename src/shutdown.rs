@@ -0,0 +1,89 @@
+//! Draining in-flight lookups on shutdown.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+
+/// A handle to shut down a [`TrustDnsResolver`] and all of its clones.
+///
+/// Created with [`TrustDnsResolver::with_shutdown_handle`].
+///
+/// [`TrustDnsResolver`]: crate::TrustDnsResolver
+/// [`TrustDnsResolver::with_shutdown_handle`]: crate::TrustDnsResolver::with_shutdown_handle
+#[derive(Debug, Clone)]
+pub struct TrustDnsResolverHandle {
+    shutdown: Arc<Shutdown>,
+}
+
+impl TrustDnsResolverHandle {
+    pub(crate) fn new(shutdown: Arc<Shutdown>) -> Self {
+        Self { shutdown }
+    }
+
+    /// Stop accepting new lookups and wait until all lookups that are in
+    /// flight have completed or were dropped.
+    ///
+    /// Lookups made after this was called fail immediately.
+    pub async fn shutdown(self) {
+        self.shutdown.state.send_modify(|state| state.closed = true);
+
+        let mut state = self.shutdown.state.subscribe();
+        // The sender is owned by `self`, so this cannot fail
+        let _ = state.wait_for(|state| state.in_flight == 0).await;
+    }
+
+    /// Get the number of lookups that are in flight.
+    #[must_use]
+    pub fn in_flight(&self) -> usize {
+        self.shutdown.state.borrow().in_flight
+    }
+}
+
+/// The shutdown state shared by a resolver and its handles.
+#[derive(Debug)]
+pub(crate) struct Shutdown {
+    state: watch::Sender<State>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct State {
+    closed: bool,
+    in_flight: usize,
+}
+
+impl Shutdown {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: watch::Sender::new(State::default()),
+        }
+    }
+
+    /// Register a new lookup, unless the resolver is shutting down.
+    pub(crate) fn start(self: &Arc<Self>) -> Option<InFlight> {
+        let started = self.state.send_if_modified(|state| {
+            if state.closed {
+                false
+            } else {
+                state.in_flight += 1;
+                true
+            }
+        });
+
+        started.then(|| InFlight {
+            shutdown: self.clone(),
+        })
+    }
+}
+
+/// A lookup in flight, which is unregistered when dropped.
+pub(crate) struct InFlight {
+    shutdown: Arc<Shutdown>,
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        self.shutdown
+            .state
+            .send_modify(|state| state.in_flight -= 1);
+    }
+}
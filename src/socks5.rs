@@ -0,0 +1,150 @@
+//! Resolving names through a SOCKS5 proxy.
+
+use std::{
+    future::Future,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+};
+
+use hyper::client::HttpConnector;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::{
+    addrs::AddrOrder,
+    service::{impl_service, Resolve},
+    DnsConnectError, ResolveError, SocketAddrs,
+};
+
+const VERSION: u8 = 0x05;
+const NO_AUTHENTICATION: u8 = 0x00;
+/// The `RESOLVE` command of the Tor SOCKS extensions.
+const RESOLVE: u8 = 0xf0;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+
+/// A resolver that resolves names through a SOCKS5 proxy instead of querying
+/// DNS locally, so that lookups do not leak outside of the proxy.
+///
+/// The addresses of a plain SOCKS5 `CONNECT` reply belong to the proxy, not
+/// to the destination, so lookups use the `RESOLVE` command of the Tor SOCKS
+/// extensions instead. Proxies without support for it, which is the case for
+/// most proxies other than Tor, will fail all lookups. Names that are IP
+/// addresses are returned without contacting the proxy.
+#[derive(Debug, Clone, Copy)]
+pub struct Socks5TrustDnsResolver {
+    proxy: SocketAddr,
+}
+
+impl Socks5TrustDnsResolver {
+    /// Create a new [`Socks5TrustDnsResolver`] that resolves names through
+    /// the SOCKS5 proxy at `proxy`.
+    #[must_use]
+    pub fn new(proxy: SocketAddr) -> Self {
+        Self { proxy }
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+}
+
+/// Resolve `name` with the `RESOLVE` command of the proxy at `proxy`.
+async fn resolve(proxy: SocketAddr, name: &str) -> Result<IpAddr, ResolveError> {
+    let name_len = u8::try_from(name.len()).map_err(|_| ResolveError::from("name is too long"))?;
+
+    let mut stream = TcpStream::connect(proxy).await?;
+
+    stream.write_all(&[VERSION, 1, NO_AUTHENTICATION]).await?;
+
+    let mut method = [0; 2];
+    stream.read_exact(&mut method).await?;
+
+    if method != [VERSION, NO_AUTHENTICATION] {
+        return Err(ResolveError::from(
+            "SOCKS5 proxy does not allow unauthenticated access",
+        ));
+    }
+
+    let mut request = Vec::with_capacity(7 + name.len());
+    request.extend_from_slice(&[VERSION, RESOLVE, 0, ATYP_DOMAIN, name_len]);
+    request.extend_from_slice(name.as_bytes());
+    request.extend_from_slice(&[0, 0]);
+    stream.write_all(&request).await?;
+
+    let mut reply = [0; 4];
+    stream.read_exact(&mut reply).await?;
+
+    if reply[0] != VERSION {
+        return Err(ResolveError::from("invalid SOCKS5 reply"));
+    }
+
+    if reply[1] != 0 {
+        return Err(ResolveError::from(format!(
+            "SOCKS5 proxy failed to resolve {name}: {}",
+            reply_message(reply[1])
+        )));
+    }
+
+    let ip = match reply[3] {
+        ATYP_IPV4 => {
+            let mut octets = [0; 4];
+            stream.read_exact(&mut octets).await?;
+
+            IpAddr::V4(Ipv4Addr::from(octets))
+        }
+        ATYP_IPV6 => {
+            let mut octets = [0; 16];
+            stream.read_exact(&mut octets).await?;
+
+            IpAddr::V6(Ipv6Addr::from(octets))
+        }
+        _ => return Err(ResolveError::from("SOCKS5 proxy did not reply with an IP")),
+    };
+
+    Ok(ip)
+}
+
+/// The description of a SOCKS5 reply code, as defined in RFC 1928.
+fn reply_message(code: u8) -> &'static str {
+    match code {
+        0x01 => "general SOCKS server failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+impl Resolve for Socks5TrustDnsResolver {
+    #[allow(clippy::type_complexity)]
+    type Future = Pin<Box<dyn Future<Output = Result<SocketAddrs, DnsConnectError>> + Send>>;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        let proxy = self.proxy;
+        let name = name.to_owned();
+
+        Box::pin(async move {
+            let ip = match name.parse::<IpAddr>() {
+                Ok(ip) => ip,
+                Err(_) => resolve(proxy, &name).await?,
+            };
+
+            Ok(SocketAddrs::new([ip], AddrOrder::Unchanged))
+        })
+    }
+}
+
+impl_service!(
+    Socks5TrustDnsResolver,
+    Pin<Box<dyn Future<Output = Result<SocketAddrs, DnsConnectError>> + Send>>
+);
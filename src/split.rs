@@ -0,0 +1,127 @@
+//! Routing lookups to different resolvers depending on the name.
+
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+
+use crate::{
+    service::{impl_service, Resolve},
+    TrustDnsResolver,
+};
+
+/// A pattern that names are matched against by a [`SplitDnsTrustDnsResolver`].
+///
+/// Names are compared case-insensitively and without a trailing dot.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum Matcher {
+    /// Matches exactly this domain.
+    Exact(String),
+    /// Matches this domain and all of its subdomains.
+    Suffix(String),
+    /// Matches all names that match this regular expression.
+    #[cfg(feature = "regex")]
+    Regex(regex::Regex),
+}
+
+impl Matcher {
+    /// Create a [`Matcher`] from a domain pattern. Patterns starting with
+    /// `*.` match the domain after it and all of its subdomains, other
+    /// patterns match exactly.
+    #[must_use]
+    pub fn new(pattern: &str) -> Self {
+        match pattern.strip_prefix("*.") {
+            Some(domain) => Self::Suffix(normalize(domain)),
+            None => Self::Exact(normalize(pattern)),
+        }
+    }
+
+    /// Whether `name` matches this pattern.
+    #[must_use]
+    pub fn matches(&self, name: &str) -> bool {
+        let name = normalize(name);
+
+        match self {
+            Self::Exact(domain) => name == normalize(domain),
+            Self::Suffix(domain) => {
+                let domain = normalize(domain);
+
+                name == domain
+                    || name
+                        .strip_suffix(domain.as_str())
+                        .is_some_and(|rest| rest.ends_with('.'))
+            }
+            #[cfg(feature = "regex")]
+            Self::Regex(regex) => regex.is_match(&name),
+        }
+    }
+}
+
+impl From<&str> for Matcher {
+    fn from(pattern: &str) -> Self {
+        Self::new(pattern)
+    }
+}
+
+/// Lowercase a name and strip its trailing dot.
+fn normalize(name: &str) -> String {
+    name.strip_suffix('.').unwrap_or(name).to_ascii_lowercase()
+}
+
+/// A resolver that sends lookups to different [`TrustDnsResolver`]s depending
+/// on the name, also known as split DNS.
+///
+/// Each lookup goes to the resolver of the first route whose [`Matcher`]
+/// matches the name, or to the fallback resolver if none does.
+#[derive(Debug, Clone)]
+pub struct SplitDnsTrustDnsResolver {
+    routes: Vec<(Matcher, TrustDnsResolver)>,
+    fallback: TrustDnsResolver,
+}
+
+impl SplitDnsTrustDnsResolver {
+    /// Create a new [`SplitDnsTrustDnsResolver`] that sends all lookups to
+    /// `fallback` until routes are added.
+    #[must_use]
+    pub fn new(fallback: TrustDnsResolver) -> Self {
+        Self {
+            routes: Vec::new(),
+            fallback,
+        }
+    }
+
+    /// Send lookups of names that match `matcher` to `resolver`. Routes are
+    /// checked in the order they were added.
+    #[must_use]
+    pub fn route(mut self, matcher: impl Into<Matcher>, resolver: TrustDnsResolver) -> Self {
+        self.routes.push((matcher.into(), resolver));
+
+        self
+    }
+
+    /// Get the resolver that lookups of `name` are sent to.
+    #[must_use]
+    pub fn resolver_for(&self, name: &str) -> &TrustDnsResolver {
+        self.routes
+            .iter()
+            .find(|(matcher, _)| matcher.matches(name))
+            .map_or(&self.fallback, |(_, resolver)| resolver)
+    }
+
+    /// Create a new [`HttpConnector`] with this resolver.
+    #[must_use]
+    pub fn into_http_connector(self) -> HttpConnector<Self> {
+        HttpConnector::new_with_resolver(self)
+    }
+}
+
+impl Resolve for SplitDnsTrustDnsResolver {
+    type Future = <TrustDnsResolver as Service<Name>>::Future;
+
+    fn resolve(&mut self, name: &str) -> Self::Future {
+        self.resolver_for(name).resolve(name.to_owned())
+    }
+}
+
+impl_service!(SplitDnsTrustDnsResolver);
@@ -0,0 +1,45 @@
+//! Streaming the addresses of a lookup.
+
+use std::{
+    net::SocketAddr,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use futures_core::Stream;
+use hyper::{client::connect::dns::Name, service::Service};
+
+use crate::{ResolveError, SocketAddrs, TrustDnsResolver};
+
+/// A stream of the addresses of a lookup, see
+/// [`TrustDnsResolver::resolve_stream`].
+pub(crate) enum ResolveStream {
+    Pending(<TrustDnsResolver as Service<Name>>::Future),
+    Ready(SocketAddrs),
+    Done,
+}
+
+impl Stream for ResolveStream {
+    type Item = Result<SocketAddr, ResolveError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+
+        if let Self::Pending(lookup) = this {
+            match lookup.as_mut().poll(cx) {
+                Poll::Ready(Ok(addrs)) => *this = Self::Ready(addrs),
+                Poll::Ready(Err(e)) => {
+                    *this = Self::Done;
+
+                    return Poll::Ready(Some(Err(e.into())));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        match this {
+            Self::Ready(addrs) => Poll::Ready(addrs.next().map(Ok)),
+            _ => Poll::Ready(None),
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Loading the resolver configuration from TOML files.
+
+use std::{error::Error, fmt, io, str::FromStr, time::Duration};
+
+use serde::Deserialize;
+
+use crate::{
+    name_servers::{self, NameServerError},
+    resolver::config::{ResolverConfig, ResolverOpts},
+};
+
+/// The `[resolver]` table of a TOML configuration file.
+///
+/// The nameservers are required, everything else is optional:
+///
+/// ```toml
+/// [resolver]
+/// nameservers = ["1.1.1.1:53", "1.0.0.1"]
+/// protocol = "udp"
+/// timeout_ms = 2000
+/// ```
+///
+/// See [`TrustDnsResolver::from_env`] for the meaning of `nameservers`,
+/// `protocol` and `tls_name`.
+///
+/// [`TrustDnsResolver::from_env`]: crate::TrustDnsResolver::from_env
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct ResolverTomlConfig {
+    /// The nameservers, IP addresses with an optional port.
+    pub nameservers: Vec<String>,
+    /// The protocol used to query the nameservers. Defaults to `udp`.
+    #[serde(default = "default_protocol")]
+    pub protocol: String,
+    /// The TLS name of the nameservers, required for `tls` and `https`.
+    pub tls_name: Option<String>,
+    /// The timeout of a single request to a nameserver in milliseconds.
+    pub timeout_ms: Option<u64>,
+    /// The number of attempts per nameserver.
+    pub attempts: Option<usize>,
+    /// The number of records to cache.
+    pub cache_size: Option<usize>,
+}
+
+/// A whole configuration file.
+#[derive(Deserialize)]
+struct TomlFile {
+    resolver: ResolverTomlConfig,
+}
+
+fn default_protocol() -> String {
+    String::from("udp")
+}
+
+impl ResolverTomlConfig {
+    /// Convert this into the [`ResolverConfig`] and [`ResolverOpts`] of a
+    /// resolver.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the nameservers or the protocol are invalid.
+    pub fn into_parts(self) -> Result<(ResolverConfig, ResolverOpts), ConfigError> {
+        let name_servers = name_servers::parse(
+            self.nameservers.iter().map(String::as_str),
+            &self.protocol,
+            self.tls_name.as_deref(),
+        )?;
+        let config = ResolverConfig::from_parts(None, vec![], name_servers);
+
+        let mut options = ResolverOpts::default();
+
+        if let Some(timeout_ms) = self.timeout_ms {
+            options.timeout = Duration::from_millis(timeout_ms);
+        }
+
+        if let Some(attempts) = self.attempts {
+            options.attempts = attempts;
+        }
+
+        if let Some(cache_size) = self.cache_size {
+            options.cache_size = cache_size;
+        }
+
+        Ok((config, options))
+    }
+}
+
+impl FromStr for ResolverTomlConfig {
+    type Err = ConfigError;
+
+    /// Parse the `[resolver]` table of a TOML document.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let file: TomlFile = toml::from_str(s).map_err(ConfigError::Parse)?;
+
+        Ok(file.resolver)
+    }
+}
+
+/// An error returned when a TOML configuration cannot be loaded.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file is not valid TOML or does not match [`ResolverTomlConfig`].
+    Parse(toml::de::Error),
+    /// There are no nameservers.
+    MissingNameserver,
+    /// A nameserver is not an IP address with an optional port.
+    InvalidNameserver(String),
+    /// The protocol is not known, or the feature for it is not enabled.
+    UnsupportedProtocol(String),
+    /// There is no TLS name, but the protocol requires it.
+    MissingTlsName,
+}
+
+impl From<NameServerError> for ConfigError {
+    fn from(error: NameServerError) -> Self {
+        match error {
+            NameServerError::Missing => Self::MissingNameserver,
+            NameServerError::Invalid(server) => Self::InvalidNameserver(server),
+            NameServerError::UnsupportedProtocol(protocol) => Self::UnsupportedProtocol(protocol),
+            NameServerError::MissingTlsName => Self::MissingTlsName,
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(_) => f.write_str("failed to read the resolver configuration"),
+            Self::Parse(_) => f.write_str("failed to parse the resolver configuration"),
+            Self::MissingNameserver => f.write_str("no nameservers configured"),
+            Self::InvalidNameserver(server) => write!(f, "invalid nameserver {server:?}"),
+            Self::UnsupportedProtocol(protocol) => write!(f, "unsupported protocol {protocol:?}"),
+            Self::MissingTlsName => f.write_str("tls_name is required for this protocol"),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
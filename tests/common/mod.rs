@@ -0,0 +1,170 @@
+//! A fake nameserver shared by the tests.
+
+// Every test crate compiles this module and uses only parts of it
+#![allow(dead_code)]
+
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use hyper_trust_dns::resolver::{
+    config::{NameServerConfigGroup, Protocol, ResolverConfig},
+    proto::{
+        op::{Message, MessageType},
+        rr::{rdata::A, RData, Record},
+        serialize::binary::{BinDecodable, BinEncodable},
+    },
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream, UdpSocket},
+};
+
+/// How a [`NameServer`] replies to a query.
+pub enum Reply {
+    /// Send the response right away.
+    Now(Message),
+    /// Send the response after the delay.
+    After(Duration, Message),
+    /// Never answer.
+    Never,
+}
+
+/// The function a [`NameServer`] answers queries with. It is called with
+/// the query and the protocol it was received over.
+type Respond = dyn Fn(&Message, Protocol) -> Reply + Send + Sync;
+
+/// A nameserver on a random local port that answers queries via UDP and
+/// TCP.
+pub struct NameServer {
+    addr: SocketAddr,
+    queries: Arc<AtomicUsize>,
+}
+
+impl NameServer {
+    /// Start a nameserver that answers queries with `respond`.
+    pub async fn start<F>(respond: F) -> Self
+    where
+        F: Fn(&Message, Protocol) -> Reply + Send + Sync + 'static,
+    {
+        let socket = Arc::new(UdpSocket::bind("127.0.0.1:0").await.unwrap());
+        let addr = socket.local_addr().unwrap();
+        let listener = TcpListener::bind(addr).await.unwrap();
+        let queries = Arc::new(AtomicUsize::new(0));
+        let respond: Arc<Respond> = Arc::new(respond);
+
+        let (counter, udp_respond) = (queries.clone(), respond.clone());
+        tokio::spawn(async move {
+            let mut buf = [0; 4096];
+
+            loop {
+                let (len, peer) = socket.recv_from(&mut buf).await.unwrap();
+                let query = Message::from_bytes(&buf[..len]).unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+
+                let (delay, response) = match udp_respond(&query, Protocol::Udp) {
+                    Reply::Now(response) => (Duration::ZERO, response),
+                    Reply::After(delay, response) => (delay, response),
+                    Reply::Never => continue,
+                };
+
+                let socket = socket.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    socket
+                        .send_to(&response.to_bytes().unwrap(), peer)
+                        .await
+                        .unwrap();
+                });
+            }
+        });
+
+        let counter = queries.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                tokio::spawn(serve_tcp(stream, counter.clone(), respond.clone()));
+            }
+        });
+
+        Self { addr, queries }
+    }
+
+    /// The address the nameserver listens on via UDP and TCP.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// A config that queries only this nameserver.
+    pub fn config(&self) -> ResolverConfig {
+        ResolverConfig::from_parts(
+            None,
+            Vec::new(),
+            NameServerConfigGroup::from_ips_clear(&[self.addr.ip()], self.addr.port(), true),
+        )
+    }
+
+    /// The number of queries received so far.
+    pub fn queries(&self) -> usize {
+        self.queries.load(Ordering::SeqCst)
+    }
+}
+
+/// Answer the queries received over a TCP connection until it is closed.
+async fn serve_tcp(mut stream: TcpStream, counter: Arc<AtomicUsize>, respond: Arc<Respond>) {
+    while let Ok(len) = stream.read_u16().await {
+        let mut buf = vec![0; usize::from(len)];
+        stream.read_exact(&mut buf).await.unwrap();
+        let query = Message::from_bytes(&buf).unwrap();
+        counter.fetch_add(1, Ordering::SeqCst);
+
+        let response = match respond(&query, Protocol::Tcp) {
+            Reply::Now(response) => response,
+            Reply::After(delay, response) => {
+                tokio::time::sleep(delay).await;
+                response
+            }
+            Reply::Never => continue,
+        };
+
+        let response = response.to_bytes().unwrap();
+        stream
+            .write_u16(u16::try_from(response.len()).unwrap())
+            .await
+            .unwrap();
+        stream.write_all(&response).await.unwrap();
+    }
+}
+
+/// An empty response to `query`.
+pub fn response(query: &Message) -> Message {
+    let mut response = Message::new();
+    response
+        .set_id(query.id())
+        .set_message_type(MessageType::Response)
+        .set_recursion_available(true)
+        .add_queries(query.queries().to_vec());
+
+    response
+}
+
+/// A response to `query` that answers every question with an A record for
+/// `ip` with a TTL of `ttl` seconds.
+pub fn a_response(query: &Message, ip: Ipv4Addr, ttl: u32) -> Message {
+    let mut response = response(query);
+
+    for question in query.queries() {
+        response.add_answer(Record::from_rdata(
+            question.name().clone(),
+            ttl,
+            RData::A(A(ip)),
+        ));
+    }
+
+    response
+}
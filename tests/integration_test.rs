@@ -0,0 +1,23 @@
+use std::net::IpAddr;
+
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::TrustDnsResolver;
+
+#[tokio::test]
+#[ignore = "queries Cloudflare's nameservers over the network"]
+async fn test_cloudflare_resolves_one_one_one_one() {
+    let mut resolver = TrustDnsResolver::cloudflare();
+    let name: Name = "one.one.one.one".parse().unwrap();
+
+    let ips: Vec<IpAddr> = resolver
+        .call(name)
+        .await
+        .unwrap()
+        .map(|addr| addr.ip())
+        .collect();
+
+    assert!(
+        ips.contains(&"1.1.1.1".parse().unwrap()) || ips.contains(&"1.0.0.1".parse().unwrap()),
+        "unexpected addresses {ips:?}"
+    );
+}
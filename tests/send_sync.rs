@@ -0,0 +1,114 @@
+//! Compile-time checks that the public types can be shared between threads.
+
+use hyper_trust_dns::{
+    BuildError, CacheStats, CachingTrustDnsResolver, CircuitBreakerResolver,
+    ConcurrentLookupLimiter, DnsConnectError, DnsProvider, EnvConfigError,
+    FallbackTrustDnsResolver, LazyTrustDnsResolver, Matcher, MultiProviderResolver,
+    PrefetchingResolver, RateLimitedResolver, ResolverPool, SharedTrustDnsResolver, SocketAddrList,
+    SocketAddrs, SplitDnsTrustDnsResolver, SrvRecord, TrustDnsHttpConnector,
+    TrustDnsHttpConnectorBuilder, TrustDnsResolver, TrustDnsResolverBuilder,
+    TrustDnsResolverHandle,
+};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_types_are_send_sync() {
+    assert_send_sync::<TrustDnsResolver>();
+    assert_send_sync::<TrustDnsResolverBuilder>();
+    assert_send_sync::<BuildError>();
+    assert_send_sync::<SocketAddrs>();
+    assert_send_sync::<SocketAddrList>();
+    assert_send_sync::<TrustDnsHttpConnector>();
+    assert_send_sync::<TrustDnsHttpConnectorBuilder>();
+    assert_send_sync::<CachingTrustDnsResolver>();
+    assert_send_sync::<CacheStats>();
+    assert_send_sync::<CircuitBreakerResolver>();
+    assert_send_sync::<ConcurrentLookupLimiter>();
+    assert_send_sync::<EnvConfigError>();
+    assert_send_sync::<FallbackTrustDnsResolver>();
+    assert_send_sync::<Matcher>();
+    assert_send_sync::<MultiProviderResolver>();
+    assert_send_sync::<RateLimitedResolver>();
+    assert_send_sync::<ResolverPool<String>>();
+    assert_send_sync::<LazyTrustDnsResolver>();
+    assert_send_sync::<TrustDnsResolverHandle>();
+    assert_send_sync::<DnsConnectError>();
+    assert_send_sync::<DnsProvider>();
+    assert_send_sync::<PrefetchingResolver>();
+    assert_send_sync::<SharedTrustDnsResolver>();
+    assert_send_sync::<SplitDnsTrustDnsResolver>();
+    assert_send_sync::<SrvRecord>();
+}
+
+#[cfg(any(feature = "rustls-native", feature = "rustls-webpki"))]
+#[test]
+fn test_rustls_types_are_send_sync() {
+    assert_send_sync::<hyper_trust_dns::RustlsHttpsConnector>();
+}
+
+#[cfg(all(
+    any(feature = "rustls-native", feature = "rustls-webpki"),
+    any(feature = "rustls-http1", feature = "rustls-http2")
+))]
+#[test]
+fn test_rustls_builder_is_send_sync() {
+    assert_send_sync::<hyper_trust_dns::RustlsHttpsConnectorBuilder>();
+}
+
+#[cfg(feature = "native-tls")]
+#[test]
+fn test_native_tls_types_are_send_sync() {
+    assert_send_sync::<hyper_trust_dns::NativeTlsHttpsConnector>();
+    assert_send_sync::<hyper_trust_dns::NativeTlsHttpsConnectorBuilder>();
+}
+
+#[cfg(feature = "dns-over-https-rustls")]
+#[test]
+fn test_doh_types_are_send_sync() {
+    assert_send_sync::<hyper_trust_dns::DohEndpointError>();
+}
+
+#[cfg(feature = "hyper1")]
+#[test]
+fn test_hyper1_types_are_send_sync() {
+    assert_send_sync::<hyper_trust_dns::hyper1::TrustDnsHttpConnector>();
+}
+
+#[cfg(feature = "keepalive")]
+#[test]
+fn test_keepalive_types_are_send_sync() {
+    assert_send_sync::<hyper_trust_dns::KeepaliveTrustDnsHttpConnector>();
+}
+
+#[cfg(feature = "tower-layer")]
+#[test]
+fn test_layer_types_are_send_sync() {
+    assert_send_sync::<hyper_trust_dns::TrustDnsLayer>();
+    assert_send_sync::<hyper_trust_dns::TrustDnsService<TrustDnsHttpConnector>>();
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn test_metrics_types_are_send_sync() {
+    assert_send_sync::<hyper_trust_dns::TrustDnsMetrics>();
+}
+
+#[cfg(feature = "mock")]
+#[test]
+fn test_mock_types_are_send_sync() {
+    assert_send_sync::<hyper_trust_dns::TrustDnsMockResolver>();
+}
+
+#[cfg(feature = "socks5")]
+#[test]
+fn test_socks5_types_are_send_sync() {
+    assert_send_sync::<hyper_trust_dns::Socks5TrustDnsResolver>();
+}
+
+#[cfg(feature = "toml-config")]
+#[test]
+fn test_toml_config_types_are_send_sync() {
+    assert_send_sync::<hyper_trust_dns::ConfigError>();
+    assert_send_sync::<hyper_trust_dns::ResolverTomlConfig>();
+}
@@ -0,0 +1,94 @@
+use std::net::SocketAddr;
+
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{SocketAddrs, TrustDnsResolver};
+
+async fn resolve_literal() -> SocketAddrs {
+    let mut resolver = TrustDnsResolver::new();
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    resolver.call(name).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_clone_does_not_consume() {
+    let addrs = resolve_literal().await;
+    let expected: SocketAddr = "127.0.0.1:0".parse().unwrap();
+
+    assert_eq!(addrs.clone().collect::<Vec<_>>(), vec![expected]);
+    assert_eq!(addrs.collect::<Vec<_>>(), vec![expected]);
+}
+
+#[tokio::test]
+async fn test_exact_size() {
+    let mut addrs = resolve_literal().await;
+
+    assert_eq!(addrs.len(), 1);
+    assert_eq!(addrs.size_hint(), (1, Some(1)));
+
+    addrs.next();
+
+    assert_eq!(addrs.len(), 0);
+}
+
+#[tokio::test]
+async fn test_into_vec() {
+    let mut addrs = resolve_literal().await;
+    let expected: SocketAddr = "127.0.0.1:0".parse().unwrap();
+
+    assert!(!addrs.is_empty());
+    assert_eq!(addrs.clone().into_vec(), vec![expected]);
+
+    addrs.next();
+
+    assert!(addrs.is_empty());
+    assert!(addrs.into_vec().is_empty());
+}
+
+#[tokio::test]
+async fn test_lookup_ip() {
+    let addrs = TrustDnsResolver::new().lookup_ip("::1").await.unwrap();
+
+    assert_eq!(addrs.into_vec(), vec!["[::1]:0".parse().unwrap()]);
+}
+
+#[tokio::test]
+async fn test_uri_lookup_uses_port() {
+    use hyper::Uri;
+
+    let mut resolver = TrustDnsResolver::new();
+
+    let uri: Uri = "http://127.0.0.1:8080/path".parse().unwrap();
+    assert_eq!(
+        resolver.call(uri).await.unwrap().into_vec(),
+        vec!["127.0.0.1:8080".parse().unwrap()]
+    );
+
+    let uri: Uri = "https://[::1]/".parse().unwrap();
+    assert_eq!(
+        resolver.call(uri).await.unwrap().into_vec(),
+        vec!["[::1]:443".parse().unwrap()]
+    );
+}
+
+#[tokio::test]
+async fn test_socket_addr_list_works() {
+    use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+
+    use hyper::Uri;
+    use hyper_trust_dns::SocketAddrList;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let mut resolver = TrustDnsResolver::new();
+    let uri: Uri = format!("http://127.0.0.1:{port}/").parse().unwrap();
+    let addrs = resolver.call(uri).await.unwrap();
+
+    let list = SocketAddrList::from(addrs.clone());
+    assert_eq!(
+        list.to_socket_addrs().unwrap().collect::<Vec<_>>(),
+        Vec::<SocketAddr>::from(addrs)
+    );
+    assert!(TcpStream::connect(list).is_ok());
+}
@@ -0,0 +1,53 @@
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::TrustDnsResolver;
+
+fn resolver() -> TrustDnsResolver {
+    TrustDnsResolver::builder()
+        .block_private_ips(true)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_private_ips_are_blocked() {
+    for ip in [
+        "127.0.0.1",
+        "10.0.0.1",
+        "192.168.1.1",
+        "169.254.0.1",
+        "::1",
+        "fd00::1",
+        "192.0.0.1",
+        // IPv4-mapped
+        "::ffff:127.0.0.1",
+        // IPv4-compatible
+        "::10.0.0.1",
+        // NAT64
+        "64:ff9b::7f00:1",
+        "64:ff9b::192.168.1.1",
+        // 6to4, 2002:c0a8:101:: embeds 192.168.1.1
+        "2002:7f00:1::1",
+        "2002:c0a8:101::1",
+    ] {
+        let name: Name = ip.parse().unwrap();
+
+        assert!(resolver().call(name).await.is_err(), "{ip} was not blocked");
+    }
+}
+
+#[tokio::test]
+async fn test_public_ips_are_allowed() {
+    let name: Name = "1.1.1.1".parse().unwrap();
+    let addrs = resolver().call(name).await.unwrap();
+
+    assert_eq!(addrs.into_vec(), vec!["1.1.1.1:0".parse().unwrap()]);
+}
+
+#[tokio::test]
+async fn test_public_ipv4_embedded_in_ipv6_is_allowed() {
+    for ip in ["64:ff9b::101:101", "2002:101:101::1", "::ffff:1.1.1.1"] {
+        let name: Name = ip.parse().unwrap();
+
+        assert!(resolver().call(name).await.is_ok(), "{ip} was blocked");
+    }
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use std::{net::Ipv4Addr, time::Duration};
+
+use common::{a_response, response, NameServer, Reply};
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{
+    resolver::{config::ResolverConfig, proto::op::ResponseCode},
+    CircuitBreakerResolver, TrustDnsResolver,
+};
+
+/// A resolver that queries the nameserver `config` and gives up after a
+/// single attempt of `timeout`.
+fn resolver_for(config: ResolverConfig, timeout: Duration) -> TrustDnsResolver {
+    TrustDnsResolver::builder()
+        .config(config)
+        .ipv4_only(true)
+        .attempts(1)
+        .timeout(timeout)
+        .build()
+        .unwrap()
+}
+
+/// A resolver whose nameserver never answers, so that every lookup times
+/// out.
+async fn failing() -> TrustDnsResolver {
+    let server = NameServer::start(|_, _| Reply::Never).await;
+
+    resolver_for(server.config(), Duration::from_millis(50))
+}
+
+/// A resolver whose nameserver answers queries for names starting with
+/// `slow.` after 800 ms and never answers other queries.
+async fn slow() -> TrustDnsResolver {
+    let server = NameServer::start(|query, _| {
+        if query.queries()[0].name().to_ascii().starts_with("slow.") {
+            let response = a_response(query, Ipv4Addr::new(192, 0, 2, 1), 60);
+
+            Reply::After(Duration::from_millis(800), response)
+        } else {
+            Reply::Never
+        }
+    })
+    .await;
+
+    resolver_for(server.config(), Duration::from_secs(1))
+}
+
+/// A resolver whose nameserver answers every query with `NXDOMAIN`.
+async fn nxdomain() -> TrustDnsResolver {
+    let server = NameServer::start(|query, _| {
+        let mut response = response(query);
+        response.set_response_code(ResponseCode::NXDomain);
+
+        Reply::Now(response)
+    })
+    .await;
+
+    resolver_for(server.config(), Duration::from_millis(50))
+}
+
+#[tokio::test]
+async fn test_breaker_opens_after_failures() {
+    let inner = failing().await;
+    let mut resolver = CircuitBreakerResolver::new(inner).failure_threshold(2);
+    let name: Name = "example.com".parse().unwrap();
+
+    assert!(resolver.call(name.clone()).await.is_err());
+    assert!(!resolver.is_open());
+
+    assert!(resolver.call(name).await.is_err());
+    assert!(resolver.is_open());
+}
+
+#[tokio::test]
+async fn test_breaker_ignores_non_existent_names() {
+    let mut resolver = CircuitBreakerResolver::new(nxdomain().await).failure_threshold(1);
+    let name: Name = "missing.example.com".parse().unwrap();
+
+    assert!(resolver.call(name.clone()).await.is_err());
+    assert!(resolver.call(name).await.is_err());
+    assert!(!resolver.is_open());
+}
+
+#[tokio::test]
+async fn test_breaker_ignores_blocked_addresses() {
+    let inner = TrustDnsResolver::builder()
+        .block_private_ips(true)
+        .build()
+        .unwrap();
+    let mut resolver = CircuitBreakerResolver::new(inner).failure_threshold(1);
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    assert!(resolver.call(name).await.is_err());
+    assert!(!resolver.is_open());
+}
+
+#[tokio::test]
+async fn test_breaker_closes_after_probe() {
+    let mut resolver = CircuitBreakerResolver::new(TrustDnsResolver::new())
+        .failure_threshold(1)
+        .open_duration(Duration::ZERO);
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    assert!(resolver.call(name).await.is_ok());
+    assert!(!resolver.is_open());
+}
+
+#[tokio::test]
+async fn test_half_open_breaker_sends_a_single_probe() {
+    let inner = failing().await;
+    let mut resolver = CircuitBreakerResolver::new(inner)
+        .failure_threshold(1)
+        .open_duration(Duration::from_millis(10));
+    let name: Name = "example.com".parse().unwrap();
+
+    assert!(resolver.call(name.clone()).await.is_err());
+    assert!(resolver.is_open());
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let probe = resolver.call(name.clone());
+    let error = resolver.call(name.clone()).await.unwrap_err();
+    assert!(error.to_string().contains("half-open"));
+
+    // The failed probe opens the circuit again
+    assert!(probe.await.is_err());
+    assert!(resolver.is_open());
+}
+
+#[tokio::test]
+async fn test_cancelled_probe_lets_another_lookup_probe() {
+    let inner = failing().await;
+    let mut resolver = CircuitBreakerResolver::new(inner)
+        .failure_threshold(1)
+        .open_duration(Duration::from_millis(10));
+    let name: Name = "example.com".parse().unwrap();
+
+    assert!(resolver.call(name.clone()).await.is_err());
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    drop(resolver.call(name.clone()));
+
+    let error = resolver.call(name).await.unwrap_err();
+    assert!(!error.to_string().contains("circuit breaker"));
+}
+
+#[tokio::test]
+async fn test_open_breaker_fails_immediately() {
+    let inner = failing().await;
+    let mut resolver = CircuitBreakerResolver::new(inner)
+        .failure_threshold(1)
+        .open_duration(Duration::from_secs(60));
+    let name: Name = "example.com".parse().unwrap();
+
+    assert!(resolver.call(name.clone()).await.is_err());
+
+    let error = resolver.call(name).await.unwrap_err();
+    assert!(error.to_string().contains("circuit breaker is open"));
+}
+
+#[tokio::test]
+async fn test_slow_success_does_not_close_an_open_breaker() {
+    let mut resolver = CircuitBreakerResolver::new(slow().await)
+        .failure_threshold(1)
+        .open_duration(Duration::from_secs(60));
+
+    // The failing lookup times out after a second and opens the circuit
+    let failing = tokio::spawn(resolver.call("fail.example.com".parse::<Name>().unwrap()));
+    tokio::time::sleep(Duration::from_millis(600)).await;
+
+    // This lookup starts while the circuit is closed and succeeds after it
+    // opened
+    let slow = resolver.call("slow.example.com".parse::<Name>().unwrap());
+
+    assert!(failing.await.unwrap().is_err());
+    assert!(resolver.is_open());
+
+    assert!(slow.await.is_ok());
+    assert!(resolver.is_open());
+}
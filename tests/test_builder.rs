@@ -0,0 +1,268 @@
+mod common;
+
+use std::{net::Ipv4Addr, sync::Arc, time::Duration};
+
+use common::{response, NameServer, Reply};
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    service::Service,
+};
+use hyper_trust_dns::{
+    resolver::{
+        config::{LookupIpStrategy, ResolverConfig, ResolverOpts},
+        proto::rr::{
+            rdata::{A, AAAA},
+            RData, Record, RecordType,
+        },
+    },
+    BuildError, DnsProvider, SharedTrustDnsResolver, TrustDnsResolver, TrustDnsResolverOpts,
+};
+
+#[test]
+fn test_builder_works() {
+    let resolver = TrustDnsResolver::builder()
+        .cache_size(64)
+        .min_ttl(Duration::from_secs(5))
+        .max_ttl(Duration::from_secs(300))
+        .timeout(Duration::from_secs(2))
+        .ipv4_only(true)
+        .build();
+
+    assert!(resolver.is_ok());
+}
+
+#[test]
+fn test_builder_rejects_conflicting_ip_strategies() {
+    let resolver = TrustDnsResolver::builder()
+        .ipv4_only(true)
+        .ipv6_only(true)
+        .build();
+
+    assert!(matches!(resolver, Err(BuildError::ConflictingIpStrategies)));
+}
+
+#[test]
+fn test_builder_rejects_configs_without_name_servers() {
+    let config = ResolverConfig::from_parts(None, Vec::new(), Vec::new());
+    let resolver = TrustDnsResolver::builder().config(config).build();
+
+    assert_eq!(resolver.unwrap_err(), BuildError::NoNameServers);
+}
+
+#[tokio::test]
+async fn test_try_with_config_and_options_validates_the_config() {
+    let resolver = TrustDnsResolver::try_with_config_and_options(
+        ResolverConfig::google(),
+        ResolverOpts::default(),
+    );
+    assert!(resolver.is_ok());
+
+    let config = ResolverConfig::from_parts(None, Vec::new(), Vec::new());
+    let error =
+        TrustDnsResolver::try_with_config_and_options(config, ResolverOpts::default()).unwrap_err();
+    assert!(error.to_string().contains("no nameservers"));
+}
+
+#[test]
+fn test_debug_shows_name_servers() {
+    let resolver = TrustDnsResolver::builder()
+        .config(ResolverConfig::cloudflare())
+        .build()
+        .unwrap();
+
+    let debug = format!("{resolver:?}");
+
+    assert!(debug.contains("1.1.1.1:53"));
+    assert_eq!(debug.matches("1.1.1.1:53").count(), 1);
+}
+
+#[cfg(any(feature = "dnssec-openssl", feature = "dnssec-ring"))]
+#[test]
+fn test_builder_dnssec_validation() {
+    let resolver = TrustDnsResolver::builder().dnssec_validation(true).build();
+
+    assert!(resolver.is_ok());
+}
+
+#[tokio::test]
+async fn test_shared_resolver() {
+    let resolver = Arc::new(TrustDnsResolver::cloudflare());
+    let connector =
+        HttpConnector::new_with_resolver(SharedTrustDnsResolver::from(resolver.clone()));
+
+    drop(connector);
+    assert!(format!("{resolver:?}").contains("1.1.1.1"));
+
+    let name: Name = "127.0.0.1".parse().unwrap();
+    let addrs = SharedTrustDnsResolver::new(resolver)
+        .call(name)
+        .await
+        .unwrap();
+    assert_eq!(addrs.into_vec(), vec!["127.0.0.1:0".parse().unwrap()]);
+}
+
+#[tokio::test]
+async fn test_health_check_domain() {
+    let resolver = TrustDnsResolver::builder()
+        .health_check_domain("127.0.0.1")
+        .block_private_ips(true)
+        .build()
+        .unwrap();
+
+    assert!(resolver.health_check().await.is_err());
+}
+
+#[tokio::test]
+async fn test_on_resolve_is_called() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use hyper::{client::connect::dns::Name, service::Service};
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+
+    let mut resolver = TrustDnsResolver::new().with_on_resolve(move |name, result, _elapsed| {
+        assert_eq!(name.as_str(), "127.0.0.1");
+        assert_eq!(result.unwrap().len(), 1);
+
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+
+    let name: Name = "127.0.0.1".parse().unwrap();
+    resolver.call(name).await.unwrap();
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[cfg(feature = "system-config")]
+#[tokio::test]
+async fn test_system_conf_or_default_works() {
+    use hyper::{client::connect::dns::Name, service::Service};
+
+    let mut resolver = TrustDnsResolver::from_system_conf_or_default();
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    assert!(resolver.call(name).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_search_domains() {
+    let resolver = TrustDnsResolver::builder()
+        .search_domains(vec![String::from("svc.cluster.local")])
+        .ndots(5)
+        .build();
+    assert!(resolver.is_ok());
+
+    let resolver = TrustDnsResolver::builder()
+        .search_domains(vec![String::from("invalid..domain")])
+        .build();
+    assert_eq!(resolver.unwrap_err(), BuildError::InvalidSearchDomain);
+}
+
+/// Start a nameserver that answers A queries with `192.0.2.1` and AAAA
+/// queries with `2001:db8::1` and `2001:db8::2`. Returns its config.
+async fn dual_stack_server() -> ResolverConfig {
+    let server = NameServer::start(|query, _| {
+        let mut response = response(query);
+
+        for question in query.queries() {
+            let name = question.name().clone();
+            let rdatas = match question.query_type() {
+                RecordType::A => vec![RData::A(A(Ipv4Addr::new(192, 0, 2, 1)))],
+                RecordType::AAAA => vec![
+                    RData::AAAA(AAAA("2001:db8::1".parse().unwrap())),
+                    RData::AAAA(AAAA("2001:db8::2".parse().unwrap())),
+                ],
+                _ => Vec::new(),
+            };
+
+            for rdata in rdatas {
+                response.add_answer(Record::from_rdata(name.clone(), 60, rdata));
+            }
+        }
+
+        Reply::Now(response)
+    })
+    .await;
+
+    server.config()
+}
+
+#[tokio::test]
+async fn test_ipv4_preferred_puts_ipv4_first() {
+    // The settings of TrustDnsResolver::with_ipv4_preferred
+    let resolver = TrustDnsResolver::builder()
+        .config(dual_stack_server().await)
+        .ip_strategy(LookupIpStrategy::Ipv4AndIpv6)
+        .prefer_ipv4(true)
+        .build()
+        .unwrap();
+    let addrs = resolver.lookup_ip("example.com").await.unwrap().into_vec();
+
+    assert_eq!(
+        addrs,
+        vec![
+            "192.0.2.1:0".parse().unwrap(),
+            "[2001:db8::1]:0".parse().unwrap(),
+            "[2001:db8::2]:0".parse().unwrap(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_pre_warm_works() {
+    let resolver = TrustDnsResolver::new();
+
+    resolver.pre_warm(&["127.0.0.1", "::1"]).await;
+}
+
+#[test]
+fn test_options_presets() {
+    let fast: ResolverOpts = TrustDnsResolverOpts::fast().into();
+    assert_eq!(fast.timeout, Duration::from_secs(1));
+    assert_eq!(fast.attempts, 1);
+
+    let resilient = TrustDnsResolverOpts::resilient().into_inner();
+    assert!(resilient.timeout > ResolverOpts::default().timeout);
+    assert!(resilient.attempts > ResolverOpts::default().attempts);
+
+    let resolver = TrustDnsResolver::builder()
+        .options(TrustDnsResolverOpts::fast().into())
+        .build();
+    assert!(resolver.is_ok());
+}
+
+#[tokio::test]
+async fn test_local_doh_uses_loopback() {
+    let resolver = TrustDnsResolver::local_doh(5053);
+
+    assert!(format!("{resolver:?}").contains("127.0.0.1:5053"));
+}
+
+#[tokio::test]
+async fn test_from_provider() {
+    let resolver = TrustDnsResolver::from_provider(DnsProvider::Quad9);
+    assert!(format!("{resolver:?}").contains("9.9.9.9:53"));
+
+    let resolver = TrustDnsResolver::from_provider(DnsProvider::Custom {
+        config: ResolverConfig::cloudflare(),
+        options: ResolverOpts::default(),
+    });
+    assert!(format!("{resolver:?}").contains("1.1.1.1:53"));
+}
+
+#[tokio::test]
+async fn test_config_port_override() {
+    let resolver = TrustDnsResolver::with_config_and_options_and_port(
+        ResolverConfig::cloudflare(),
+        ResolverOpts::default(),
+        5353,
+    );
+
+    let debug = format!("{resolver:?}");
+    assert!(debug.contains("1.1.1.1:5353"));
+    assert!(!debug.contains("1.1.1.1:53,"));
+}
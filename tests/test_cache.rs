@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{CachingTrustDnsResolver, TrustDnsResolver};
+
+async fn lookup(resolver: &mut CachingTrustDnsResolver, name: &str) {
+    let name: Name = name.parse().unwrap();
+
+    resolver.call(name).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_cache_hits() {
+    let mut resolver =
+        CachingTrustDnsResolver::new(TrustDnsResolver::new(), Duration::from_secs(60), 16);
+
+    lookup(&mut resolver, "127.0.0.1").await;
+    lookup(&mut resolver, "127.0.0.1").await;
+
+    let stats = resolver.cache_stats();
+
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.size, 1);
+}
+
+#[tokio::test]
+async fn test_cache_expires_after_max_ttl() {
+    let mut resolver =
+        CachingTrustDnsResolver::new(TrustDnsResolver::new(), Duration::from_millis(50), 16);
+
+    lookup(&mut resolver, "127.0.0.1").await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    lookup(&mut resolver, "127.0.0.1").await;
+
+    assert_eq!(resolver.cache_stats().misses, 2);
+}
+
+#[tokio::test]
+async fn test_cache_respects_capacity() {
+    let mut resolver =
+        CachingTrustDnsResolver::new(TrustDnsResolver::new(), Duration::from_secs(60), 1);
+
+    lookup(&mut resolver, "127.0.0.1").await;
+    lookup(&mut resolver, "127.0.0.2").await;
+
+    assert_eq!(resolver.cache_stats().size, 1);
+}
+
+#[tokio::test]
+async fn test_cache_serves_stale_entries() {
+    let mut resolver =
+        CachingTrustDnsResolver::new(TrustDnsResolver::new(), Duration::from_millis(50), 16)
+            .stale_while_revalidate(Duration::from_secs(60));
+
+    lookup(&mut resolver, "127.0.0.1").await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    lookup(&mut resolver, "127.0.0.1").await;
+
+    // Give the background refresh a chance to finish
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    lookup(&mut resolver, "127.0.0.1").await;
+
+    let stats = resolver.cache_stats();
+
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.hits, 2);
+    assert_eq!(stats.stale_hits, 1);
+}
+
+#[tokio::test]
+async fn test_cache_with_unbounded_durations() {
+    let mut resolver = CachingTrustDnsResolver::new(TrustDnsResolver::new(), Duration::MAX, 1)
+        .stale_while_revalidate(Duration::MAX);
+
+    lookup(&mut resolver, "127.0.0.1").await;
+    lookup(&mut resolver, "127.0.0.1").await;
+    // Evicting the entry compares never expiring entries
+    lookup(&mut resolver, "127.0.0.2").await;
+
+    let stats = resolver.cache_stats();
+
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 2);
+    assert_eq!(stats.size, 1);
+}
+
+#[tokio::test]
+async fn test_cache_clear_cache_for() {
+    let mut resolver =
+        CachingTrustDnsResolver::new(TrustDnsResolver::new(), Duration::from_secs(60), 16);
+
+    lookup(&mut resolver, "127.0.0.1").await;
+    lookup(&mut resolver, "127.0.0.2").await;
+    resolver.clear_cache_for("127.0.0.1");
+    lookup(&mut resolver, "127.0.0.1").await;
+    lookup(&mut resolver, "127.0.0.2").await;
+
+    let stats = resolver.cache_stats();
+
+    assert_eq!(stats.misses, 3);
+    assert_eq!(stats.hits, 1);
+}
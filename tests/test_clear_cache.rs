@@ -0,0 +1,28 @@
+mod common;
+
+use std::net::Ipv4Addr;
+
+use common::{a_response, NameServer, Reply};
+use hyper_trust_dns::TrustDnsResolver;
+
+#[tokio::test]
+async fn test_clear_cache_forces_new_queries() {
+    let server = NameServer::start(|query, _| {
+        Reply::Now(a_response(query, Ipv4Addr::new(192, 0, 2, 1), 300))
+    })
+    .await;
+    let resolver = TrustDnsResolver::builder()
+        .config(server.config())
+        .ipv4_only(true)
+        .build()
+        .unwrap();
+
+    resolver.lookup_ip("example.com").await.unwrap();
+    resolver.lookup_ip("example.com").await.unwrap();
+    assert_eq!(server.queries(), 1);
+
+    resolver.clone().clear_cache();
+
+    resolver.lookup_ip("example.com").await.unwrap();
+    assert_eq!(server.queries(), 2);
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use std::net::Ipv4Addr;
+
+use common::{response, NameServer, Reply};
+use hyper_trust_dns::{
+    resolver::{
+        config::{Protocol, ResolverConfig},
+        error::ResolveErrorKind,
+        proto::{
+            op::{Message, ResponseCode},
+            rr::{
+                rdata::{opt::EdnsCode, A},
+                RData, Record, RecordType,
+            },
+        },
+    },
+    BuildError, TrustDnsResolver,
+};
+
+/// Answer `query` like a nameserver that only knows the addresses of names if
+/// the query carries the EDNS Client Subnet option. AAAA queries fail with
+/// `SERVFAIL`, names starting with `missing.` do not exist and the responses
+/// for names starting with `big.` are truncated, unless they are sent via
+/// TCP.
+fn answer(query: &Message, protocol: Protocol) -> Reply {
+    let mut response = response(query);
+
+    let has_subnet = query
+        .extensions()
+        .as_ref()
+        .is_some_and(|edns| edns.option(EdnsCode::Subnet).is_some());
+
+    for question in query.queries() {
+        let name = question.name().to_ascii();
+
+        if name.starts_with("missing.") {
+            response.set_response_code(ResponseCode::NXDomain);
+        } else if question.query_type() == RecordType::AAAA {
+            response.set_response_code(ResponseCode::ServFail);
+        } else if name.starts_with("big.") && protocol != Protocol::Tcp {
+            response.set_truncated(true);
+        } else if has_subnet && question.query_type() == RecordType::A {
+            response.add_answer(Record::from_rdata(
+                question.name().clone(),
+                60,
+                RData::A(A(Ipv4Addr::new(192, 0, 2, 1))),
+            ));
+        }
+    }
+
+    Reply::Now(response)
+}
+
+/// Start a nameserver that answers queries with [`answer`].
+async fn ecs_server() -> ResolverConfig {
+    NameServer::start(answer).await.config()
+}
+
+#[tokio::test]
+async fn test_edns_client_subnet_is_sent() {
+    let resolver = TrustDnsResolver::builder()
+        .config(ecs_server().await)
+        .ipv4_only(true)
+        .edns_client_subnet("203.0.113.7/24".parse().unwrap())
+        .build()
+        .unwrap();
+
+    let addrs = resolver.lookup_ip("example.com").await.unwrap();
+
+    assert_eq!(addrs.into_vec(), vec!["192.0.2.1:0".parse().unwrap()]);
+}
+
+#[tokio::test]
+async fn test_without_edns_client_subnet() {
+    let resolver = TrustDnsResolver::builder()
+        .config(ecs_server().await)
+        .ipv4_only(true)
+        .build()
+        .unwrap();
+
+    assert!(resolver.lookup_ip("example.com").await.is_err());
+}
+
+#[tokio::test]
+async fn test_edns_client_subnet_keeps_addresses_if_aaaa_fails() {
+    let resolver = TrustDnsResolver::builder()
+        .config(ecs_server().await)
+        .edns_client_subnet("203.0.113.7/24".parse().unwrap())
+        .build()
+        .unwrap();
+
+    let addrs = resolver.lookup_ip("example.com").await.unwrap();
+
+    assert_eq!(addrs.into_vec(), vec!["192.0.2.1:0".parse().unwrap()]);
+}
+
+#[tokio::test]
+async fn test_edns_client_subnet_retries_truncated_responses_via_tcp() {
+    let resolver = TrustDnsResolver::builder()
+        .config(ecs_server().await)
+        .ipv4_only(true)
+        .edns_client_subnet("203.0.113.7/24".parse().unwrap())
+        .build()
+        .unwrap();
+
+    let addrs = resolver.lookup_ip("big.example.com").await.unwrap();
+
+    assert_eq!(addrs.into_vec(), vec!["192.0.2.1:0".parse().unwrap()]);
+}
+
+#[tokio::test]
+async fn test_edns_client_subnet_reports_non_existent_names() {
+    let resolver = TrustDnsResolver::builder()
+        .config(ecs_server().await)
+        .ipv4_only(true)
+        .edns_client_subnet("203.0.113.7/24".parse().unwrap())
+        .build()
+        .unwrap();
+
+    let error = resolver.lookup_ip("missing.example.com").await.unwrap_err();
+
+    assert!(matches!(
+        error.kind(),
+        ResolveErrorKind::NoRecordsFound {
+            response_code: ResponseCode::NXDomain,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_edns_client_subnet_requires_unencrypted_nameservers() {
+    let config = ResolverConfig::from_parts(None, Vec::new(), Vec::new());
+    let result = TrustDnsResolver::builder()
+        .config(config)
+        .edns_client_subnet("203.0.113.7/24".parse().unwrap())
+        .build();
+
+    assert_eq!(result.unwrap_err(), BuildError::NoUnencryptedNameServers);
+}
+
+#[cfg(feature = "dns-over-rustls")]
+#[test]
+fn test_edns_client_subnet_is_not_sent_unencrypted_to_tls_nameservers() {
+    let result = TrustDnsResolver::builder()
+        .config(ResolverConfig::cloudflare_tls())
+        .edns_client_subnet("203.0.113.7/24".parse().unwrap())
+        .build();
+
+    assert_eq!(result.unwrap_err(), BuildError::NoUnencryptedNameServers);
+}
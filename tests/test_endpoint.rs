@@ -0,0 +1,54 @@
+#[cfg(feature = "dns-over-https-rustls")]
+mod tests {
+    use hyper_trust_dns::{DohEndpointError, TrustDnsResolver};
+
+    #[test]
+    fn test_doh_endpoint_works() {
+        assert!(TrustDnsResolver::with_doh_endpoint("https://1.1.1.1/dns-query").is_ok());
+        assert!(TrustDnsResolver::with_doh_endpoint("https://[2606:4700:4700::1111]:443").is_ok());
+    }
+
+    #[test]
+    fn test_doh_endpoint_rejects_invalid_urls() {
+        assert!(matches!(
+            TrustDnsResolver::with_doh_endpoint("http://1.1.1.1/dns-query"),
+            Err(DohEndpointError::InvalidUrl)
+        ));
+        assert!(matches!(
+            TrustDnsResolver::with_doh_endpoint("https://1.1.1.1:dns/dns-query"),
+            Err(DohEndpointError::InvalidUrl)
+        ));
+        assert!(matches!(
+            TrustDnsResolver::with_doh_endpoint("https://1.1.1.1/resolve"),
+            Err(DohEndpointError::UnsupportedPath)
+        ));
+    }
+}
+
+#[cfg(feature = "dns-over-rustls")]
+mod dot_tests {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use hyper::{Body, Client, Request};
+    use hyper_trust_dns::TrustDnsResolver;
+
+    #[tokio::test]
+    async fn test_dot_endpoint_works() {
+        let resolver = TrustDnsResolver::with_dot_endpoint(
+            IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)),
+            853,
+            "cloudflare-dns.com",
+        );
+        let client = Client::builder().build(resolver.into_http_connector());
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("http://www.google.com/")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = client.request(request).await.unwrap();
+
+        assert_eq!(response.status(), 200);
+    }
+}
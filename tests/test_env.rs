@@ -0,0 +1,38 @@
+use std::env;
+
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{EnvConfigError, TrustDnsResolver};
+
+// The environment is shared by all threads, so everything is checked in a
+// single test
+#[tokio::test]
+async fn test_from_env() {
+    env::remove_var("TRUST_DNS_NAMESERVER");
+    env::remove_var("TRUST_DNS_PROTOCOL");
+    env::remove_var("TRUST_DNS_TLS_NAME");
+    assert_eq!(
+        TrustDnsResolver::from_env().unwrap_err(),
+        EnvConfigError::MissingNameserver
+    );
+
+    env::set_var("TRUST_DNS_NAMESERVER", "8.8.8.8:53,not-an-ip");
+    assert_eq!(
+        TrustDnsResolver::from_env().unwrap_err(),
+        EnvConfigError::InvalidNameserver(String::from("not-an-ip"))
+    );
+
+    env::set_var("TRUST_DNS_NAMESERVER", "8.8.8.8:53, 1.1.1.1");
+    env::set_var("TRUST_DNS_PROTOCOL", "carrier-pigeon");
+    assert_eq!(
+        TrustDnsResolver::from_env().unwrap_err(),
+        EnvConfigError::UnsupportedProtocol(String::from("carrier-pigeon"))
+    );
+
+    env::set_var("TRUST_DNS_PROTOCOL", "tcp");
+    let resolver = TrustDnsResolver::from_env().unwrap();
+    assert!(format!("{resolver:?}").contains("1.1.1.1:53"));
+
+    let mut resolver = resolver;
+    let name: Name = "127.0.0.1".parse().unwrap();
+    assert!(resolver.call(name).await.is_ok());
+}
@@ -0,0 +1,37 @@
+use std::{error::Error, io};
+
+use hyper_trust_dns::{
+    resolve_error_to_io, resolver::error::ResolveErrorKind, DnsConnectError, ResolveError,
+};
+
+#[test]
+fn test_resolve_error_to_io_maps_kinds() {
+    let timeout = resolve_error_to_io(ResolveError::from(ResolveErrorKind::Timeout));
+    assert_eq!(timeout.kind(), io::ErrorKind::TimedOut);
+
+    let no_connections = resolve_error_to_io(ResolveError::from(ResolveErrorKind::NoConnections));
+    assert_eq!(no_connections.kind(), io::ErrorKind::NotConnected);
+
+    let refused = io::Error::from(io::ErrorKind::ConnectionRefused);
+    let refused = resolve_error_to_io(ResolveError::from(refused));
+    assert_eq!(refused.kind(), io::ErrorKind::ConnectionRefused);
+
+    let other = resolve_error_to_io(ResolveError::from("something went wrong"));
+    assert_eq!(other.kind(), io::ErrorKind::Other);
+    assert_eq!(other.to_string(), "something went wrong");
+}
+
+#[test]
+fn test_dns_connect_error_has_source() {
+    let refused = io::Error::new(io::ErrorKind::ConnectionRefused, "refused");
+    let error = DnsConnectError::from(ResolveError::from(refused));
+
+    let source = error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+    assert_eq!(source.kind(), io::ErrorKind::ConnectionRefused);
+    assert!(error.to_string().contains("refused"));
+
+    let timeout = DnsConnectError::from(ResolveError::from(ResolveErrorKind::Timeout));
+    assert!(matches!(timeout.kind(), ResolveErrorKind::Timeout));
+    assert!(timeout.source().is_none());
+    assert_eq!(io::Error::from(timeout).kind(), io::ErrorKind::TimedOut);
+}
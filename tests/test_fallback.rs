@@ -0,0 +1,26 @@
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{FallbackTrustDnsResolver, TrustDnsResolver};
+
+#[tokio::test]
+async fn test_fallback_moves_on_after_errors() {
+    let failing = TrustDnsResolver::builder()
+        .block_private_ips(true)
+        .build()
+        .unwrap();
+
+    let mut resolver = FallbackTrustDnsResolver::new(vec![failing, TrustDnsResolver::new()]);
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    assert_eq!(
+        resolver.call(name).await.unwrap().into_vec(),
+        vec!["127.0.0.1:0".parse().unwrap()]
+    );
+}
+
+#[tokio::test]
+async fn test_fallback_without_resolvers_fails() {
+    let mut resolver = FallbackTrustDnsResolver::new(Vec::new());
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    assert!(resolver.call(name).await.is_err());
+}
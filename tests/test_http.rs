@@ -1,5 +1,80 @@
-use hyper::{Body, Client, Request};
-use hyper_trust_dns::TrustDnsResolver;
+use std::{
+    io::{Read, Write},
+    net::{IpAddr, SocketAddr, TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+
+use hyper::{service::Service, Body, Client, Request};
+use hyper_trust_dns::{TrustDnsHttpConnector, TrustDnsResolver};
+use socket2::{Domain, SockRef, Socket, Type};
+
+/// Answer a single HTTP request on a local port with an empty 200 response.
+/// Returns the address of the server and a handle that yields the address of
+/// the client.
+fn local_server() -> (SocketAddr, thread::JoinHandle<SocketAddr>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let handle = thread::spawn(move || {
+        let (mut stream, peer) = listener.accept().unwrap();
+
+        let mut buf = [0; 1024];
+        let _ = stream.read(&mut buf).unwrap();
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+
+        peer
+    });
+
+    (addr, handle)
+}
+
+async fn get_local(connector: TrustDnsHttpConnector) -> SocketAddr {
+    let (addr, server) = local_server();
+    let client = Client::builder().build::<_, Body>(connector);
+
+    let response = client
+        .get(format!("http://{addr}/").parse().unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    server.join().unwrap()
+}
+
+/// Connect to a local port with `connector`. Returns the connected stream
+/// to inspect its socket options.
+async fn connect_local(mut connector: TrustDnsHttpConnector) -> tokio::net::TcpStream {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    connector
+        .call(format!("http://{addr}/").parse().unwrap())
+        .await
+        .unwrap()
+}
+
+/// Listen on a local port whose accept queue is full, so that connection
+/// attempts hang until they time out. The returned streams fill the queue
+/// and have to be kept alive for the duration of the test.
+fn unresponsive_server() -> (SocketAddr, Socket, Vec<TcpStream>) {
+    let socket = Socket::new(Domain::IPV4, Type::STREAM, None).unwrap();
+    socket
+        .bind(&"127.0.0.1:0".parse::<SocketAddr>().unwrap().into())
+        .unwrap();
+    socket.listen(0).unwrap();
+    let addr = socket.local_addr().unwrap().as_socket().unwrap();
+
+    let mut queued = Vec::new();
+    while let Ok(stream) = TcpStream::connect_timeout(&addr, Duration::from_millis(100)) {
+        queued.push(stream);
+    }
+
+    (addr, socket, queued)
+}
 
 #[tokio::test]
 async fn test_lookup_works() {
@@ -16,3 +91,122 @@ async fn test_lookup_works() {
 
     assert_eq!(response.status(), 200);
 }
+
+#[tokio::test]
+async fn test_health_check_works() {
+    let resolver = TrustDnsResolver::default();
+
+    assert!(resolver.health_check().await.is_ok());
+}
+
+#[tokio::test]
+async fn test_local_address_works() {
+    let local: IpAddr = "127.0.0.1".parse().unwrap();
+    let connector = TrustDnsResolver::default().into_http_connector_with_local_address(local);
+
+    assert_eq!(get_local(connector).await.ip(), local);
+}
+
+#[tokio::test]
+async fn test_connect_timeout_works() {
+    let (addr, _listener, _queued) = unresponsive_server();
+    let mut connector =
+        TrustDnsResolver::default().into_http_connector_with_timeout(Duration::from_millis(200));
+
+    let start = Instant::now();
+    let error = connector
+        .call(format!("http://{addr}/").parse().unwrap())
+        .await
+        .unwrap_err();
+
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert!(format!("{error:?}").contains("TimedOut"));
+}
+
+#[tokio::test]
+async fn test_keepalive_connector_works() {
+    let connector =
+        TrustDnsResolver::default().into_http_connector_with_keepalive(Duration::from_secs(60));
+    let stream = connect_local(connector).await;
+    let socket = SockRef::from(&stream);
+
+    assert!(socket.keepalive().unwrap());
+    assert_eq!(socket.keepalive_time().unwrap(), Duration::from_secs(60));
+    assert!(socket.nodelay().unwrap());
+}
+
+#[tokio::test]
+async fn test_from_resolver_works() {
+    let connector: TrustDnsHttpConnector = TrustDnsResolver::default().into();
+
+    get_local(connector).await;
+}
+
+#[tokio::test]
+async fn test_connector_builder_works() {
+    let connector = TrustDnsResolver::default()
+        .into_http_connector_builder()
+        .connect_timeout(Duration::from_secs(5))
+        .nodelay(true)
+        .keepalive(Duration::from_secs(60))
+        .enforce_http(true)
+        .build();
+
+    get_local(connector).await;
+}
+
+#[tokio::test]
+async fn test_connector_for_tls_accepts_https() {
+    let mut connector = TrustDnsResolver::default().into_http_connector();
+    assert!(connector
+        .call("https://127.0.0.1:1/".parse().unwrap())
+        .await
+        .unwrap_err()
+        .to_string()
+        .contains("URL"));
+
+    let mut connector = TrustDnsResolver::default().into_http_connector_for_tls();
+    let error = connector
+        .call("https://127.0.0.1:1/".parse().unwrap())
+        .await
+        .unwrap_err();
+    assert!(!error.to_string().contains("URL"));
+}
+
+#[cfg(feature = "client")]
+#[tokio::test]
+async fn test_hyper_client_works() {
+    let (addr, server) = local_server();
+    let client = TrustDnsResolver::default().into_hyper_client();
+
+    let response = client
+        .get(format!("http://{addr}/").parse().unwrap())
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    server.join().unwrap();
+}
+
+#[tokio::test]
+async fn test_connector_options_work() {
+    use hyper_trust_dns::HttpConnectorOptions;
+
+    let options = HttpConnectorOptions {
+        connect_timeout: Some(Duration::from_secs(5)),
+        nodelay: true,
+        reuse_address: true,
+        send_buffer_size: Some(64 * 1024),
+        recv_buffer_size: Some(64 * 1024),
+        ..HttpConnectorOptions::default()
+    };
+    let connector = TrustDnsResolver::default().into_http_connector_with_options(&options);
+    let stream = connect_local(connector).await;
+    let socket = SockRef::from(&stream);
+
+    assert!(socket.nodelay().unwrap());
+    assert!(socket.reuse_address().unwrap());
+    // Linux doubles the requested sizes for its bookkeeping
+    assert!(socket.send_buffer_size().unwrap() >= 64 * 1024);
+    assert!(socket.recv_buffer_size().unwrap() >= 64 * 1024);
+}
@@ -0,0 +1,32 @@
+#[cfg(feature = "hyper1")]
+mod tests {
+    use hyper_trust_dns::TrustDnsResolver;
+    use hyper_util::{client::legacy::Client, rt::TokioExecutor};
+
+    #[tokio::test]
+    async fn test_hyper1_lookup_works() {
+        let connector = TrustDnsResolver::default().into_hyper1_http_connector();
+        let client = Client::builder(TokioExecutor::new()).build::<_, String>(connector);
+
+        let response = client
+            .get("http://www.google.com/".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+    }
+
+    #[cfg(all(feature = "hyper1-rustls", feature = "rustls-webpki"))]
+    #[tokio::test]
+    async fn test_hyper1_rustls_webpki_roots_works() {
+        let connector = TrustDnsResolver::default().into_hyper1_rustls_webpki_https_connector();
+        let client = Client::builder(TokioExecutor::new()).build::<_, String>(connector);
+
+        let response = client
+            .get("https://www.google.com/".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+    }
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use std::net::Ipv4Addr;
+
+use common::{a_response, response, NameServer, Reply};
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{
+    resolver::proto::op::{Message, ResponseCode},
+    TrustDnsResolver,
+};
+
+/// Answer `query` like a nameserver that only knows the punycode names of a
+/// few internationalized domains.
+fn answer(query: &Message) -> Reply {
+    let known = [
+        "xn--mnchen-3ya.de.",
+        "xn--bcher-kva.example.",
+        "xn--r8jz45g.jp.",
+    ];
+    let name = query.queries()[0].name().to_ascii();
+
+    if known.contains(&name.as_str()) {
+        Reply::Now(a_response(query, Ipv4Addr::new(192, 0, 2, 1), 60))
+    } else {
+        let mut response = response(query);
+        response.set_response_code(ResponseCode::NXDomain);
+
+        Reply::Now(response)
+    }
+}
+
+#[tokio::test]
+async fn test_internationalized_names_are_resolved() {
+    let mut resolver = TrustDnsResolver::builder()
+        .config(NameServer::start(|query, _| answer(query)).await.config())
+        .ipv4_only(true)
+        .build()
+        .unwrap();
+
+    for name in [
+        "münchen.de",
+        "Bücher.example",
+        "例え.jp",
+        "xn--mnchen-3ya.de",
+    ] {
+        let name: Name = name.parse().unwrap();
+        let addrs = resolver.call(name).await.unwrap();
+
+        assert_eq!(addrs.into_vec(), vec!["192.0.2.1:0".parse().unwrap()]);
+    }
+}
@@ -0,0 +1,25 @@
+#[cfg(feature = "keepalive")]
+mod tests {
+    use std::{net::TcpListener, time::Duration};
+
+    use hyper::service::Service;
+    use hyper_trust_dns::{KeepaliveTrustDnsHttpConnector, TrustDnsResolver};
+    use socket2::SockRef;
+
+    #[tokio::test]
+    async fn test_keepalive_is_enabled() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let mut connector = KeepaliveTrustDnsHttpConnector::new(
+            TrustDnsResolver::default().into_http_connector(),
+            Duration::from_secs(30),
+        );
+        let stream = connector
+            .call(format!("http://{addr}/").parse().unwrap())
+            .await
+            .unwrap();
+
+        assert!(SockRef::from(&stream).keepalive().unwrap());
+    }
+}
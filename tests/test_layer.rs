@@ -0,0 +1,42 @@
+#[cfg(feature = "tower-layer")]
+mod tests {
+    use std::{
+        convert::Infallible,
+        future::{self, Ready},
+        net::SocketAddr,
+        task::{Context, Poll},
+    };
+
+    use hyper::{client::connect::dns::Name, service::Service};
+    use hyper_trust_dns::{SocketAddrs, TrustDnsLayer, TrustDnsResolver};
+    use tower_layer::Layer;
+
+    /// A service that returns the addresses it is called with.
+    #[derive(Clone)]
+    struct Collect;
+
+    impl Service<SocketAddrs> for Collect {
+        type Response = Vec<SocketAddr>;
+        type Error = Infallible;
+        type Future = Ready<Result<Self::Response, Self::Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, addrs: SocketAddrs) -> Self::Future {
+            future::ready(Ok(addrs.into_vec()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_layer_resolves_names() {
+        let mut service = TrustDnsLayer::new(TrustDnsResolver::new()).layer(Collect);
+        let name: Name = "127.0.0.1".parse().unwrap();
+
+        assert_eq!(
+            service.call(name).await.unwrap(),
+            vec!["127.0.0.1:0".parse().unwrap()]
+        );
+    }
+}
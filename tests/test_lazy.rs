@@ -0,0 +1,23 @@
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{
+    resolver::config::{ResolverConfig, ResolverOpts},
+    TrustDnsResolver,
+};
+
+#[test]
+fn test_lazy_resolver_outside_runtime() {
+    let mut resolver =
+        TrustDnsResolver::new_lazy(ResolverConfig::default(), ResolverOpts::default());
+    assert!(format!("{resolver:?}").contains("None"));
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let name: Name = "127.0.0.1".parse().unwrap();
+    let addrs = runtime.block_on(resolver.call(name)).unwrap();
+
+    assert_eq!(addrs.into_vec(), vec!["127.0.0.1:0".parse().unwrap()]);
+    assert!(format!("{resolver:?}").contains("TrustDnsResolver"));
+}
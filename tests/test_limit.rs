@@ -0,0 +1,40 @@
+use std::future::poll_fn;
+
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{ConcurrentLookupLimiter, TrustDnsResolver};
+
+#[tokio::test]
+async fn test_limiter_resolves() {
+    let mut resolver = ConcurrentLookupLimiter::new(TrustDnsResolver::new(), 1);
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    poll_fn(|cx| Service::<Name>::poll_ready(&mut resolver, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        resolver.call(name.clone()).await.unwrap().into_vec(),
+        vec!["127.0.0.1:0".parse().unwrap()]
+    );
+
+    // The permit is released after the lookup
+    poll_fn(|cx| Service::<Name>::poll_ready(&mut resolver, cx))
+        .await
+        .unwrap();
+    assert!(resolver.call(name).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_limiter_is_not_ready_without_permits() {
+    let mut resolver = ConcurrentLookupLimiter::new(TrustDnsResolver::new(), 1);
+    let mut other = resolver.clone();
+
+    poll_fn(|cx| Service::<Name>::poll_ready(&mut resolver, cx))
+        .await
+        .unwrap();
+
+    let ready = poll_fn(|cx| {
+        std::task::Poll::Ready(Service::<Name>::poll_ready(&mut other, cx).is_ready())
+    })
+    .await;
+    assert!(!ready);
+}
@@ -0,0 +1,50 @@
+#[cfg(feature = "metrics")]
+mod tests {
+    use hyper::{client::connect::dns::Name, service::Service};
+    use hyper_trust_dns::{TrustDnsMetrics, TrustDnsResolver};
+    use prometheus::Registry;
+
+    #[tokio::test]
+    async fn test_metrics_are_recorded() {
+        let registry = Registry::new();
+        let metrics = TrustDnsMetrics::new(&registry).unwrap();
+
+        let mut resolver = TrustDnsResolver::builder()
+            .metrics(metrics.clone())
+            .build()
+            .unwrap();
+        let mut failing = TrustDnsResolver::builder()
+            .metrics(metrics)
+            .block_private_ips(true)
+            .build()
+            .unwrap();
+
+        let name: Name = "127.0.0.1".parse().unwrap();
+        resolver.call(name.clone()).await.unwrap();
+        failing.call(name).await.unwrap_err();
+
+        let families = registry.gather();
+        let duration = families
+            .iter()
+            .find(|family| family.name() == "trust_dns_lookup_duration_seconds")
+            .unwrap();
+        let errors = families
+            .iter()
+            .find(|family| family.name() == "trust_dns_lookup_errors_total")
+            .unwrap();
+
+        assert_eq!(
+            duration.get_metric()[0].get_histogram().get_sample_count(),
+            2
+        );
+        assert_eq!(errors.get_metric().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_register_once() {
+        let registry = Registry::new();
+
+        assert!(TrustDnsMetrics::new(&registry).is_ok());
+        assert!(TrustDnsMetrics::new(&registry).is_err());
+    }
+}
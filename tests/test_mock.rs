@@ -0,0 +1,72 @@
+#[cfg(feature = "mock")]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io::{Read, Write},
+        net::{IpAddr, TcpListener},
+        thread,
+    };
+
+    use hyper::{client::connect::dns::Name, service::Service, Body, Client};
+    use hyper_trust_dns::{
+        DnsConnectError, LocalHttpConnector, LocalResolver, TrustDnsMockResolver,
+    };
+
+    fn mock_resolver() -> TrustDnsMockResolver {
+        let ips: Vec<IpAddr> = vec!["192.0.2.1".parse().unwrap(), "2001:db8::1".parse().unwrap()];
+
+        TrustDnsMockResolver::new(HashMap::from([("example.com".to_owned(), ips)]))
+    }
+
+    #[tokio::test]
+    async fn test_mock_resolves_known_names() {
+        let name: Name = "example.com".parse().unwrap();
+        let addrs = mock_resolver().call(name).await.unwrap();
+
+        assert_eq!(
+            addrs.into_vec(),
+            vec![
+                "192.0.2.1:0".parse().unwrap(),
+                "[2001:db8::1]:0".parse().unwrap()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_rejects_unknown_names() {
+        let name: Name = "example.org".parse().unwrap();
+
+        let error: DnsConnectError = mock_resolver().call(name).await.unwrap_err();
+        assert!(error.to_string().contains("example.org"));
+    }
+
+    #[tokio::test]
+    async fn test_local_connector_reaches_mapped_name() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf).unwrap();
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .unwrap();
+        });
+
+        let ips: Vec<IpAddr> = vec!["127.0.0.1".parse().unwrap()];
+        let connector: LocalHttpConnector =
+            LocalResolver::new(HashMap::from([("api.example.com".to_owned(), ips)]))
+                .into_http_connector();
+        let client = Client::builder().build::<_, Body>(connector);
+
+        let response = client
+            .get(format!("http://api.example.com:{port}/").parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,124 @@
+mod common;
+
+use std::{net::IpAddr, time::Duration};
+
+use common::{response, NameServer, Reply};
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{
+    resolver::{
+        config::{LookupIpStrategy, ResolverConfig},
+        proto::rr::{
+            rdata::{A, AAAA},
+            RData, Record, RecordType,
+        },
+    },
+    MultiProviderResolver, TrustDnsResolver,
+};
+
+/// Start a nameserver that answers the queries for the address family of
+/// `ip` with `ip` and all other queries without records. Returns its config.
+async fn server(ip: IpAddr) -> ResolverConfig {
+    let server = NameServer::start(move |query, _| {
+        let mut response = response(query);
+
+        for question in query.queries() {
+            let rdata = match (question.query_type(), ip) {
+                (RecordType::A, IpAddr::V4(ip)) => RData::A(A(ip)),
+                (RecordType::AAAA, IpAddr::V6(ip)) => RData::AAAA(AAAA(ip)),
+                _ => continue,
+            };
+
+            response.add_answer(Record::from_rdata(question.name().clone(), 60, rdata));
+        }
+
+        Reply::Now(response)
+    })
+    .await;
+
+    server.config()
+}
+
+/// A resolver that looks up both address families with the nameserver
+/// `config`.
+fn resolver_for(config: ResolverConfig, prefer_ipv4: bool) -> TrustDnsResolver {
+    TrustDnsResolver::builder()
+        .config(config)
+        .ip_strategy(LookupIpStrategy::Ipv4AndIpv6)
+        .prefer_ipv4(prefer_ipv4)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_multi_merges_results() {
+    let mut resolver =
+        MultiProviderResolver::new(vec![TrustDnsResolver::new(), TrustDnsResolver::new()]);
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    assert_eq!(
+        resolver.call(name).await.unwrap().into_vec(),
+        vec!["127.0.0.1:0".parse().unwrap()]
+    );
+}
+
+#[tokio::test]
+async fn test_multi_requires_min_success() {
+    let failing = TrustDnsResolver::builder()
+        .block_private_ips(true)
+        .build()
+        .unwrap();
+
+    let mut resolver = MultiProviderResolver::new(vec![failing.clone(), TrustDnsResolver::new()]);
+    let name: Name = "127.0.0.1".parse().unwrap();
+    assert!(resolver.call(name).await.is_ok());
+
+    let mut resolver =
+        MultiProviderResolver::new(vec![failing, TrustDnsResolver::new()]).min_success(2);
+    let name: Name = "127.0.0.1".parse().unwrap();
+    assert!(resolver.call(name).await.is_err());
+}
+
+#[tokio::test]
+async fn test_multi_without_resolvers_fails() {
+    let mut resolver = MultiProviderResolver::new(Vec::new());
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    assert!(resolver.call(name).await.is_err());
+}
+
+#[tokio::test]
+async fn test_multi_cancels_lookups_after_min_success() {
+    let silent = NameServer::start(|_, _| Reply::Never).await;
+    let hanging = TrustDnsResolver::builder()
+        .config(silent.config())
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap();
+    let working = resolver_for(server("192.0.2.1".parse().unwrap()).await, false);
+
+    let mut resolver = MultiProviderResolver::new(vec![hanging, working]);
+    let name: Name = "example.com".parse().unwrap();
+    let addrs = tokio::time::timeout(Duration::from_secs(5), resolver.call(name))
+        .await
+        .expect("the lookup waited for the hanging resolver")
+        .unwrap();
+
+    assert_eq!(addrs.into_vec(), vec!["192.0.2.1:0".parse().unwrap()]);
+}
+
+#[tokio::test]
+async fn test_multi_orders_merged_addresses() {
+    let ipv6 = resolver_for(server("2001:db8::1".parse().unwrap()).await, true);
+    let ipv4 = resolver_for(server("192.0.2.1".parse().unwrap()).await, true);
+
+    let mut resolver = MultiProviderResolver::new(vec![ipv6, ipv4]).min_success(2);
+    let name: Name = "example.com".parse().unwrap();
+
+    assert_eq!(
+        resolver.call(name).await.unwrap().into_vec(),
+        vec![
+            "192.0.2.1:0".parse().unwrap(),
+            "[2001:db8::1]:0".parse().unwrap(),
+        ]
+    );
+}
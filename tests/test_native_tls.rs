@@ -1,5 +1,7 @@
 #[cfg(feature = "native-tls")]
 mod tests {
+    use std::time::Duration;
+
     use hyper::{Body, Client, Request};
     use hyper_trust_dns::TrustDnsResolver;
 
@@ -18,4 +20,66 @@ mod tests {
 
         assert_eq!(response.status(), 200);
     }
+
+    #[tokio::test]
+    async fn test_native_tls_custom_connector_works() {
+        let tls = tokio_native_tls::native_tls::TlsConnector::builder()
+            .min_protocol_version(Some(tokio_native_tls::native_tls::Protocol::Tlsv12))
+            .build()
+            .unwrap();
+        let connector =
+            TrustDnsResolver::default().into_native_tls_https_connector_with_config(tls);
+        let client = Client::builder().build(connector);
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("https://www.google.com/")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = client.request(request).await.unwrap();
+
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_native_tls_connector_builder_works() {
+        let connector = TrustDnsResolver::default()
+            .into_native_tls_https_connector_builder()
+            .connect_timeout(Duration::from_secs(5))
+            .nodelay(true)
+            .build();
+        let client = Client::builder().build(connector);
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("https://www.google.com/")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = client.request(request).await.unwrap();
+
+        assert_eq!(response.status(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_native_tls_https_only_is_configurable() {
+        use hyper::service::Service;
+
+        let uri: hyper::Uri = "http://127.0.0.1:1/".parse().unwrap();
+
+        let mut connector = TrustDnsResolver::default()
+            .into_native_tls_https_connector_builder()
+            .https_only(true)
+            .build();
+        let error = connector.call(uri.clone()).await.unwrap_err();
+        assert!(error.to_string().contains("https required"));
+
+        let mut connector = TrustDnsResolver::default()
+            .into_native_tls_https_connector_builder()
+            .https_only(false)
+            .build();
+        let error = connector.call(uri).await.unwrap_err();
+        assert!(!error.to_string().contains("https required"));
+    }
 }
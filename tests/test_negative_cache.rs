@@ -0,0 +1,56 @@
+mod common;
+
+use std::time::Duration;
+
+use common::{response, NameServer, Reply};
+use hyper_trust_dns::{
+    resolver::proto::{
+        op::{Message, ResponseCode},
+        rr::{rdata::SOA, Name, RData, Record},
+    },
+    TrustDnsResolver,
+};
+
+/// Answer `query` with `NXDOMAIN` and an SOA record with a TTL of 0.
+fn nxdomain(query: &Message) -> Reply {
+    let zone = Name::from_ascii("example.com.").unwrap();
+    let soa = SOA::new(zone.clone(), zone.clone(), 1, 3600, 600, 86400, 0);
+
+    let mut response = response(query);
+    response
+        .set_response_code(ResponseCode::NXDomain)
+        .add_name_server(Record::from_rdata(zone, 0, RData::SOA(soa)));
+
+    Reply::Now(response)
+}
+
+#[tokio::test]
+async fn test_negative_cache_ttl_caches_nxdomain() {
+    let server = NameServer::start(|query, _| nxdomain(query)).await;
+    let resolver = TrustDnsResolver::builder()
+        .config(server.config())
+        .ipv4_only(true)
+        .negative_cache_ttl(Duration::from_secs(60))
+        .build()
+        .unwrap();
+
+    assert!(resolver.lookup_ip("missing.example.com").await.is_err());
+    assert!(resolver.lookup_ip("missing.example.com").await.is_err());
+
+    assert_eq!(server.queries(), 1);
+}
+
+#[tokio::test]
+async fn test_nxdomain_with_ttl_0_is_not_cached() {
+    let server = NameServer::start(|query, _| nxdomain(query)).await;
+    let resolver = TrustDnsResolver::builder()
+        .config(server.config())
+        .ipv4_only(true)
+        .build()
+        .unwrap();
+
+    assert!(resolver.lookup_ip("missing.example.com").await.is_err());
+    assert!(resolver.lookup_ip("missing.example.com").await.is_err());
+
+    assert_eq!(server.queries(), 2);
+}
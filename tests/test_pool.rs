@@ -0,0 +1,33 @@
+use hyper_trust_dns::{
+    resolver::config::{ResolverConfig, ResolverOpts},
+    ResolverPool,
+};
+
+#[tokio::test]
+async fn test_pool_creates_resolvers_once() {
+    let pool = ResolverPool::new();
+
+    pool.get_or_create("eu", ResolverConfig::cloudflare(), ResolverOpts::default());
+    let resolver = pool.get_or_create("eu", ResolverConfig::google(), ResolverOpts::default());
+    pool.get_or_create("us", ResolverConfig::google(), ResolverOpts::default());
+
+    assert!(format!("{resolver:?}").contains("1.1.1.1:53"));
+
+    let mut keys = pool.keys();
+    keys.sort_unstable();
+    assert_eq!(keys, vec!["eu", "us"]);
+}
+
+#[tokio::test]
+async fn test_pool_removes_resolvers() {
+    let pool = ResolverPool::new();
+    pool.get_or_create(1, ResolverConfig::default(), ResolverOpts::default());
+    pool.get_or_create(2, ResolverConfig::default(), ResolverOpts::default());
+
+    assert!(pool.remove(&1).is_some());
+    assert!(pool.get(&1).is_none());
+    assert_eq!(pool.keys(), vec![2]);
+
+    pool.shutdown_all();
+    assert!(pool.keys().is_empty());
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use std::{net::Ipv4Addr, time::Duration};
+
+use common::{a_response, NameServer, Reply};
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{PrefetchingResolver, TrustDnsResolver};
+
+#[tokio::test]
+async fn test_prefetch_refreshes_before_expiry() {
+    // Answers with a TTL of 2 seconds
+    let server =
+        NameServer::start(|query, _| Reply::Now(a_response(query, Ipv4Addr::new(192, 0, 2, 1), 2)))
+            .await;
+    let inner = TrustDnsResolver::builder()
+        .config(server.config())
+        .ipv4_only(true)
+        .cache_size(0)
+        .build()
+        .unwrap();
+    let mut resolver = PrefetchingResolver::new(inner).refresh_threshold(0.5);
+    let name: Name = "example.com".parse().unwrap();
+
+    assert!(resolver.call(name.clone()).await.is_ok());
+    assert!(resolver.call(name.clone()).await.is_ok());
+    assert_eq!(server.queries(), 1);
+
+    // Less than half of the TTL remains, the cached addresses are returned
+    // and refreshed in the background
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let addrs = resolver.call(name.clone()).await.unwrap();
+    assert_eq!(addrs.into_vec(), vec!["192.0.2.1:0".parse().unwrap()]);
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(server.queries(), 2);
+
+    // The refreshed entry is fresh again
+    assert!(resolver.call(name).await.is_ok());
+    assert_eq!(server.queries(), 2);
+}
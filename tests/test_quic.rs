@@ -0,0 +1,11 @@
+#![cfg(feature = "dns-over-quic")]
+
+use hyper_trust_dns::TrustDnsResolver;
+
+#[tokio::test]
+async fn test_adguard_quic_lookup_works() {
+    let resolver = TrustDnsResolver::adguard_quic();
+    let addrs = resolver.lookup_ip("example.com").await.unwrap();
+
+    assert!(!addrs.is_empty());
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use std::{
+    net::Ipv4Addr,
+    time::{Duration, Instant},
+};
+
+use common::{response, NameServer, Reply};
+use hyper_trust_dns::{
+    resolver::{
+        config::LookupIpStrategy,
+        proto::rr::{
+            rdata::{A, AAAA},
+            RData, Record, RecordType,
+        },
+    },
+    TrustDnsResolver,
+};
+
+/// Start a nameserver that answers A queries with `192.0.2.1` right away and
+/// AAAA queries with `2001:db8::1` after `aaaa_delay`.
+async fn delaying_server(aaaa_delay: Duration) -> NameServer {
+    NameServer::start(move |query, _| {
+        let question = &query.queries()[0];
+        let mut response = response(query);
+
+        match question.query_type() {
+            RecordType::A => {
+                let rdata = RData::A(A(Ipv4Addr::new(192, 0, 2, 1)));
+                response.add_answer(Record::from_rdata(question.name().clone(), 60, rdata));
+
+                Reply::Now(response)
+            }
+            RecordType::AAAA => {
+                let rdata = RData::AAAA(AAAA("2001:db8::1".parse().unwrap()));
+                response.add_answer(Record::from_rdata(question.name().clone(), 60, rdata));
+
+                Reply::After(aaaa_delay, response)
+            }
+            _ => Reply::Now(response),
+        }
+    })
+    .await
+}
+
+#[tokio::test]
+async fn test_race_queries_does_not_wait_for_a_hanging_family() {
+    let server = delaying_server(Duration::from_secs(3)).await;
+    let resolver = TrustDnsResolver::builder()
+        .config(server.config())
+        .ip_strategy(LookupIpStrategy::Ipv4AndIpv6)
+        .race_queries(true)
+        .build()
+        .unwrap();
+
+    let start = Instant::now();
+    let addrs = resolver.lookup_ip("example.com").await.unwrap();
+
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(addrs.into_vec(), vec!["192.0.2.1:0".parse().unwrap()]);
+}
+
+#[tokio::test]
+async fn test_race_queries_keeps_a_slightly_slower_family() {
+    let server = delaying_server(Duration::from_millis(10)).await;
+    let resolver = TrustDnsResolver::builder()
+        .config(server.config())
+        .ip_strategy(LookupIpStrategy::Ipv4AndIpv6)
+        .happy_eyeballs(false)
+        .race_queries(true)
+        .build()
+        .unwrap();
+
+    let addrs = resolver.lookup_ip("example.com").await.unwrap();
+
+    assert_eq!(
+        addrs.into_vec(),
+        vec![
+            "192.0.2.1:0".parse().unwrap(),
+            "[2001:db8::1]:0".parse().unwrap(),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_race_queries_with_ipv4_only_skips_aaaa() {
+    let server = delaying_server(Duration::ZERO).await;
+    let resolver = TrustDnsResolver::builder()
+        .config(server.config())
+        .ipv4_only(true)
+        .race_queries(true)
+        .build()
+        .unwrap();
+
+    let addrs = resolver.lookup_ip("example.com").await.unwrap();
+
+    assert_eq!(addrs.into_vec(), vec!["192.0.2.1:0".parse().unwrap()]);
+    // Only the A query was sent
+    assert_eq!(server.queries(), 1);
+}
+
+#[tokio::test]
+async fn test_race_queries_with_fallback_strategy_uses_preferred_family() {
+    let server = delaying_server(Duration::ZERO).await;
+    let resolver = TrustDnsResolver::builder()
+        .config(server.config())
+        .ip_strategy(LookupIpStrategy::Ipv6thenIpv4)
+        .race_queries(true)
+        .build()
+        .unwrap();
+
+    let addrs = resolver.lookup_ip("example.com").await.unwrap();
+
+    assert_eq!(addrs.into_vec(), vec!["[2001:db8::1]:0".parse().unwrap()]);
+}
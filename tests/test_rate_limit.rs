@@ -0,0 +1,108 @@
+mod common;
+
+use std::{net::Ipv4Addr, time::Duration};
+
+use common::{a_response, NameServer, Reply};
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{RateLimitedResolver, TrustDnsResolver};
+
+/// Start a nameserver that answers every query with `192.0.2.1` and a TTL of
+/// one second. Returns a resolver that queries it.
+async fn short_ttl_resolver() -> TrustDnsResolver {
+    let server =
+        NameServer::start(|query, _| Reply::Now(a_response(query, Ipv4Addr::new(192, 0, 2, 1), 1)))
+            .await;
+
+    TrustDnsResolver::builder()
+        .config(server.config())
+        .ipv4_only(true)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_rate_limit_serves_last_result() {
+    let mut resolver =
+        RateLimitedResolver::new(TrustDnsResolver::new(), 1, Duration::from_secs(60));
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    let first = resolver.call(name.clone()).await.unwrap().into_vec();
+    let second = resolver.call(name).await.unwrap().into_vec();
+
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn test_rate_limit_fails_without_result() {
+    let failing = TrustDnsResolver::builder()
+        .block_private_ips(true)
+        .build()
+        .unwrap();
+    let mut resolver = RateLimitedResolver::new(failing, 1, Duration::from_secs(60));
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    assert!(resolver.call(name.clone()).await.is_err());
+
+    let error = resolver.call(name).await.unwrap_err();
+    assert!(error.to_string().contains("rate limit"));
+}
+
+#[tokio::test]
+async fn test_rate_limit_window_slides() {
+    let failing = TrustDnsResolver::builder()
+        .block_private_ips(true)
+        .build()
+        .unwrap();
+    let mut resolver = RateLimitedResolver::new(failing, 1, Duration::from_millis(100));
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    assert!(resolver.call(name.clone()).await.is_err());
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let error = resolver.call(name).await.unwrap_err();
+    assert!(!error.to_string().contains("rate limit"));
+}
+
+#[tokio::test]
+async fn test_rate_limit_with_unbounded_window() {
+    let mut resolver = RateLimitedResolver::new(TrustDnsResolver::new(), 1, Duration::MAX);
+    let name: Name = "127.0.0.1".parse().unwrap();
+
+    let first = resolver.call(name.clone()).await.unwrap().into_vec();
+    let second = resolver.call(name).await.unwrap().into_vec();
+
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn test_rate_limit_does_not_serve_expired_results() {
+    let mut resolver =
+        RateLimitedResolver::new(short_ttl_resolver().await, 1, Duration::from_secs(60));
+    let name: Name = "example.com".parse().unwrap();
+
+    assert!(resolver.call(name.clone()).await.is_ok());
+    assert!(resolver.call(name.clone()).await.is_ok());
+
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+
+    let error = resolver.call(name).await.unwrap_err();
+    assert!(error.to_string().contains("rate limit"));
+}
+
+#[tokio::test]
+async fn test_rate_limit_forgets_names_with_expired_results() {
+    let mut resolver =
+        RateLimitedResolver::new(short_ttl_resolver().await, 1, Duration::from_millis(100));
+
+    let name: Name = "first.example.com".parse().unwrap();
+    assert!(resolver.call(name).await.is_ok());
+    assert!(format!("{resolver:?}").contains("first.example.com"));
+
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+
+    // Looking up another name sweeps the windows of all names
+    let name: Name = "second.example.com".parse().unwrap();
+    assert!(resolver.call(name).await.is_ok());
+    assert!(!format!("{resolver:?}").contains("first.example.com"));
+}
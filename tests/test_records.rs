@@ -0,0 +1,33 @@
+use hyper_trust_dns::TrustDnsResolver;
+
+#[tokio::test]
+async fn test_lookup_srv_works() {
+    let records = TrustDnsResolver::default()
+        .lookup_srv("_imaps._tcp.gmail.com")
+        .await
+        .unwrap();
+
+    assert!(records.iter().any(|record| record.port() == 993));
+}
+
+#[tokio::test]
+async fn test_lookup_txt_works() {
+    let records = TrustDnsResolver::default()
+        .lookup_txt("gmail.com")
+        .await
+        .unwrap();
+
+    assert!(records.iter().any(|record| record.starts_with("v=spf1")));
+}
+
+#[tokio::test]
+async fn test_reverse_lookup_works() {
+    let names = TrustDnsResolver::default()
+        .reverse_lookup("1.1.1.1".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(names
+        .iter()
+        .any(|name| name.to_ascii() == "one.one.one.one."));
+}
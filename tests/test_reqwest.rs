@@ -0,0 +1,25 @@
+#[cfg(feature = "reqwest")]
+mod tests {
+    use hyper_trust_dns::{ReqwestClientBuilderExt, TrustDnsResolver};
+    use reqwest::dns::Resolve;
+
+    #[tokio::test]
+    async fn test_resolve_works() {
+        let resolver = TrustDnsResolver::new();
+        let addrs = Resolve::resolve(&resolver, "127.0.0.1".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            addrs.collect::<Vec<_>>(),
+            vec!["127.0.0.1:0".parse().unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_builder_ext_works() {
+        let client = reqwest::Client::builder().trust_dns_cloudflare().build();
+
+        assert!(client.is_ok());
+    }
+}
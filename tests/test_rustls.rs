@@ -86,4 +86,157 @@ mod tests {
 
         assert_eq!(response.status(), 200);
     }
+
+    /// The self-signed certificate of `localhost` that [`tls_server`] uses.
+    ///
+    /// Generated with `openssl req -x509 -newkey ec -pkeyopt
+    /// ec_paramgen_curve:prime256v1 -nodes -days 36500 -subj /CN=localhost
+    /// -addext subjectAltName=DNS:localhost -addext
+    /// basicConstraints=critical,CA:FALSE`.
+    #[cfg(feature = "rustls-webpki")]
+    fn certificate() -> rustls::Certificate {
+        rustls::Certificate(include_bytes!("certs/localhost.der").to_vec())
+    }
+
+    /// A root store that only trusts [`certificate`].
+    #[cfg(feature = "rustls-webpki")]
+    fn local_roots() -> rustls::RootCertStore {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(&certificate()).unwrap();
+
+        roots
+    }
+
+    /// Start an HTTPS server with [`certificate`] that answers every request
+    /// with an empty `200 OK`. Returns its URL.
+    #[cfg(feature = "rustls-webpki")]
+    async fn tls_server() -> hyper::Uri {
+        use std::sync::Arc;
+
+        use tokio::{
+            io::{AsyncReadExt, AsyncWriteExt},
+            net::TcpListener,
+        };
+        use tokio_rustls::TlsAcceptor;
+
+        let key = rustls::PrivateKey(include_bytes!("certs/localhost.key.der").to_vec());
+        let tls_config = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(vec![certificate()], key)
+            .unwrap();
+        let acceptor = TlsAcceptor::from(Arc::new(tls_config));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let acceptor = acceptor.clone();
+
+                tokio::spawn(async move {
+                    // Clients that do not trust the certificate abort the
+                    // handshake
+                    let Ok(mut stream) = acceptor.accept(stream).await else {
+                        return;
+                    };
+
+                    let mut buf = [0; 1024];
+                    let _ = stream.read(&mut buf).await;
+                    let _ = stream
+                        .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+                        .await;
+                });
+            }
+        });
+
+        format!("https://localhost:{port}/").parse().unwrap()
+    }
+
+    #[cfg(feature = "rustls-webpki")]
+    #[tokio::test]
+    async fn test_rustls_custom_config_works() {
+        let uri = tls_server().await;
+
+        let mut tls_config = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(local_roots())
+            .with_no_client_auth();
+        tls_config.alpn_protocols = vec![b"h2".to_vec()];
+
+        let connector =
+            TrustDnsResolver::default().into_rustls_https_connector_with_config(tls_config);
+        let client: Client<_> = Client::builder().build(connector);
+        let response = client.get(uri.clone()).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let tls_config = rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(rustls::RootCertStore::empty())
+            .with_no_client_auth();
+
+        let connector =
+            TrustDnsResolver::default().into_rustls_https_connector_with_config(tls_config);
+        let client: Client<_> = Client::builder().build(connector);
+        assert!(client.get(uri).await.is_err());
+    }
+
+    #[cfg(feature = "rustls-webpki")]
+    #[tokio::test]
+    async fn test_rustls_connector_builder_works() {
+        let uri = tls_server().await;
+
+        let connector = TrustDnsResolver::default()
+            .into_rustls_https_connector_builder()
+            .custom_roots(vec![certificate()])
+            .https_only(false)
+            .nodelay(true)
+            .build();
+        let client: Client<_> = Client::builder().build(connector);
+        let response = client.get(uri.clone()).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let connector = TrustDnsResolver::default()
+            .into_rustls_https_connector_builder()
+            .custom_roots(Vec::new())
+            .build();
+        let client: Client<_> = Client::builder().build(connector);
+        assert!(client.get(uri).await.is_err());
+    }
+
+    #[cfg(feature = "rustls-webpki")]
+    #[tokio::test]
+    async fn test_rustls_custom_roots_work() {
+        let uri = tls_server().await;
+
+        let connector =
+            TrustDnsResolver::default().into_rustls_https_connector_with_roots(local_roots());
+        let client: Client<_> = Client::builder().build(connector);
+        let response = client.get(uri.clone()).await.unwrap();
+        assert_eq!(response.status(), 200);
+
+        let connector = TrustDnsResolver::default()
+            .into_rustls_https_connector_with_roots(rustls::RootCertStore::empty());
+        let client: Client<_> = Client::builder().build(connector);
+        assert!(client.get(uri.clone()).await.is_err());
+
+        // The webpki roots do not include the self-signed certificate either
+        let connector = TrustDnsResolver::default().into_rustls_webpki_https_connector();
+        let client: Client<_> = Client::builder().build(connector);
+        assert!(client.get(uri).await.is_err());
+    }
+
+    #[cfg(all(feature = "client", feature = "rustls-webpki"))]
+    #[tokio::test]
+    async fn test_rustls_webpki_client_works() {
+        let client = TrustDnsResolver::default().into_hyper_https_client_rustls_webpki();
+
+        let response = client
+            .get("https://www.google.com/".parse().unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+    }
 }
@@ -0,0 +1,53 @@
+mod common;
+
+use std::net::Ipv4Addr;
+
+use common::{a_response, NameServer, Reply};
+use hyper_trust_dns::{
+    resolver::config::{NameServerConfig, Protocol, ResolverConfig, ResolverOpts},
+    TrustDnsResolver,
+};
+
+/// Start a nameserver that answers every query with `127.0.0.1`.
+async fn localhost_server() -> NameServer {
+    NameServer::start(|query, _| Reply::Now(a_response(query, Ipv4Addr::LOCALHOST, 60))).await
+}
+
+#[tokio::test]
+async fn test_shuffle_dns_servers_queries_every_nameserver() {
+    let first = localhost_server().await;
+    let second = localhost_server().await;
+    let config = ResolverConfig::from_parts(
+        None,
+        Vec::new(),
+        vec![
+            NameServerConfig::new(first.addr(), Protocol::Udp),
+            NameServerConfig::new(second.addr(), Protocol::Udp),
+        ],
+    );
+
+    // Query one nameserver at a time, so the order decides which one is used
+    let mut options = ResolverOpts::default();
+    options.num_concurrent_reqs = 1;
+
+    let resolver = TrustDnsResolver::builder()
+        .config(config)
+        .options(options)
+        .ipv4_only(true)
+        .shuffle_dns_servers(true)
+        .build()
+        .unwrap();
+
+    for i in 0..40 {
+        assert!(resolver
+            .lookup_ip(&format!("host{i}.example.com"))
+            .await
+            .is_ok());
+    }
+
+    let first_queries = first.queries();
+    let second_queries = second.queries();
+    assert_eq!(first_queries + second_queries, 40);
+    assert!(first_queries > 0);
+    assert!(second_queries > 0);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{NameServer, Reply};
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::TrustDnsResolver;
+
+#[tokio::test]
+async fn test_shutdown_rejects_new_lookups() {
+    let (mut resolver, handle) = TrustDnsResolver::new().with_shutdown_handle();
+
+    let name: Name = "127.0.0.1".parse().unwrap();
+    assert!(resolver.call(name).await.is_ok());
+
+    handle.shutdown().await;
+
+    let name: Name = "127.0.0.1".parse().unwrap();
+    assert!(resolver.call(name).await.is_err());
+}
+
+#[tokio::test]
+async fn test_shutdown_waits_for_lookups() {
+    let silent = NameServer::start(|_, _| Reply::Never).await;
+    let (mut resolver, handle) = TrustDnsResolver::builder()
+        .config(silent.config())
+        .timeout(Duration::from_millis(300))
+        .attempts(1)
+        .build()
+        .unwrap()
+        .with_shutdown_handle();
+
+    let name: Name = "example.com".parse().unwrap();
+    let lookup = tokio::spawn(resolver.call(name));
+
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(handle.in_flight(), 1);
+
+    let start = Instant::now();
+    handle.clone().shutdown().await;
+
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert_eq!(handle.in_flight(), 0);
+    assert!(lookup.await.unwrap().is_err());
+}
@@ -0,0 +1,60 @@
+#[cfg(feature = "socks5")]
+mod tests {
+    use hyper::{client::connect::dns::Name, service::Service};
+    use hyper_trust_dns::Socks5TrustDnsResolver;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// Accept a single connection and answer a `RESOLVE` request with
+    /// 192.0.2.1, or with `reply_code` if it is not 0.
+    async fn fake_proxy(reply_code: u8) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+
+            let mut greeting = [0; 3];
+            stream.read_exact(&mut greeting).await.unwrap();
+            stream.write_all(&[0x05, 0x00]).await.unwrap();
+
+            let mut header = [0; 5];
+            stream.read_exact(&mut header).await.unwrap();
+            assert_eq!(&header[..4], &[0x05, 0xf0, 0x00, 0x03]);
+
+            let mut name = vec![0; usize::from(header[4]) + 2];
+            stream.read_exact(&mut name).await.unwrap();
+            assert_eq!(&name[..name.len() - 2], b"example.com");
+
+            stream
+                .write_all(&[0x05, reply_code, 0x00, 0x01, 192, 0, 2, 1, 0, 0])
+                .await
+                .unwrap();
+        });
+
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_socks5_resolves_through_proxy() {
+        let mut resolver = Socks5TrustDnsResolver::new(fake_proxy(0).await);
+        let name: Name = "example.com".parse().unwrap();
+
+        assert_eq!(
+            resolver.call(name).await.unwrap().into_vec(),
+            vec!["192.0.2.1:0".parse().unwrap()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_socks5_reports_proxy_errors() {
+        let mut resolver = Socks5TrustDnsResolver::new(fake_proxy(0x04).await);
+        let name: Name = "example.com".parse().unwrap();
+
+        let error = resolver.call(name).await.map(|_| ()).unwrap_err();
+
+        assert!(error.to_string().contains("host unreachable"));
+    }
+}
@@ -0,0 +1,39 @@
+use hyper_trust_dns::{Matcher, SplitDnsTrustDnsResolver, TrustDnsResolver};
+
+#[test]
+fn test_exact_matcher() {
+    let matcher = Matcher::new("example.com");
+
+    assert!(matcher.matches("example.com"));
+    assert!(matcher.matches("EXAMPLE.com."));
+    assert!(!matcher.matches("www.example.com"));
+    assert!(!matcher.matches("example.org"));
+}
+
+#[test]
+fn test_suffix_matcher() {
+    let matcher = Matcher::new("*.corp.example.com");
+
+    assert!(matcher.matches("corp.example.com"));
+    assert!(matcher.matches("git.corp.example.com"));
+    assert!(!matcher.matches("notcorp.example.com"));
+    assert!(!matcher.matches("example.com"));
+}
+
+#[tokio::test]
+async fn test_split_dns_routes_by_name() {
+    let resolver = SplitDnsTrustDnsResolver::new(TrustDnsResolver::google())
+        .route("*.corp.example.com", TrustDnsResolver::cloudflare());
+
+    assert!(format!("{:?}", resolver.resolver_for("git.corp.example.com")).contains("1.1.1.1"));
+    assert!(format!("{:?}", resolver.resolver_for("example.com")).contains("8.8.8.8"));
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn test_regex_matcher() {
+    let matcher = Matcher::Regex(regex::Regex::new(r"^db\d+\.internal$").unwrap());
+
+    assert!(matcher.matches("db1.internal"));
+    assert!(!matcher.matches("web1.internal"));
+}
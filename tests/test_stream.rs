@@ -0,0 +1,31 @@
+#[cfg(feature = "futures")]
+mod tests {
+    use futures_util::StreamExt;
+    use hyper::client::connect::dns::Name;
+    use hyper_trust_dns::TrustDnsResolver;
+
+    #[tokio::test]
+    async fn test_resolve_stream_yields_addresses() {
+        let resolver = TrustDnsResolver::new();
+        let name: Name = "127.0.0.1".parse().unwrap();
+
+        let addrs: Vec<_> = resolver.resolve_stream(&name).collect().await;
+
+        assert_eq!(addrs.len(), 1);
+        assert_eq!(addrs[0].as_ref().unwrap(), &"127.0.0.1:0".parse().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_stream_yields_error() {
+        let resolver = TrustDnsResolver::builder()
+            .block_private_ips(true)
+            .build()
+            .unwrap();
+        let name: Name = "127.0.0.1".parse().unwrap();
+
+        let addrs: Vec<_> = resolver.resolve_stream(&name).collect().await;
+
+        assert_eq!(addrs.len(), 1);
+        assert!(addrs[0].is_err());
+    }
+}
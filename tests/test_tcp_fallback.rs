@@ -0,0 +1,69 @@
+mod common;
+
+use std::net::{Ipv4Addr, SocketAddr};
+
+use common::{response, NameServer, Reply};
+use hyper_trust_dns::{
+    resolver::{
+        config::Protocol,
+        proto::{
+            op::Message,
+            rr::{rdata::A, RData, Record},
+        },
+    },
+    TrustDnsResolver,
+};
+
+/// Answer `query` with a truncated response with one address over UDP and
+/// the full response with two addresses over TCP.
+fn truncating(query: &Message, protocol: Protocol) -> Reply {
+    let name = query.queries()[0].name().clone();
+    let ips: &[Ipv4Addr] = match protocol {
+        Protocol::Tcp => &[Ipv4Addr::new(192, 0, 2, 1), Ipv4Addr::new(192, 0, 2, 2)],
+        _ => &[Ipv4Addr::new(192, 0, 2, 1)],
+    };
+
+    let mut response = response(query);
+    response.set_truncated(protocol != Protocol::Tcp);
+    for &ip in ips {
+        response.add_answer(Record::from_rdata(name.clone(), 60, RData::A(A(ip))));
+    }
+
+    Reply::Now(response)
+}
+
+#[tokio::test]
+async fn test_truncated_responses_are_retried_over_tcp() {
+    let server = NameServer::start(truncating).await;
+    let resolver = TrustDnsResolver::builder()
+        .config(server.config())
+        .ipv4_only(true)
+        .tcp_fallback(true)
+        .build()
+        .unwrap();
+
+    let addrs = resolver.lookup_ip("example.com").await.unwrap();
+
+    assert_eq!(addrs.into_vec().len(), 2);
+    // The truncated query via UDP and the retry via TCP
+    assert_eq!(server.queries(), 2);
+}
+
+#[tokio::test]
+async fn test_truncated_responses_are_returned_without_tcp_fallback() {
+    let server = NameServer::start(truncating).await;
+    let resolver = TrustDnsResolver::builder()
+        .config(server.config())
+        .ipv4_only(true)
+        .tcp_fallback(false)
+        .build()
+        .unwrap();
+
+    let addrs = resolver.lookup_ip("example.com").await.unwrap();
+
+    assert_eq!(
+        addrs.into_vec(),
+        vec![SocketAddr::from((Ipv4Addr::new(192, 0, 2, 1), 0))]
+    );
+    assert_eq!(server.queries(), 1);
+}
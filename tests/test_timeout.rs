@@ -0,0 +1,48 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{NameServer, Reply};
+use hyper::{client::connect::dns::Name, service::Service};
+use hyper_trust_dns::{resolver::error::ResolveErrorKind, TrustDnsResolver};
+
+#[tokio::test]
+async fn test_timeout_is_enforced() {
+    let silent = NameServer::start(|_, _| Reply::Never).await;
+    let mut resolver = TrustDnsResolver::builder()
+        .config(silent.config())
+        .attempts(10)
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    let name: Name = "www.google.com".parse().unwrap();
+    let start = Instant::now();
+    let result = resolver.call(name).await;
+
+    assert!(matches!(
+        result.map(|_| ()).unwrap_err().kind(),
+        ResolveErrorKind::Timeout
+    ));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn test_timeouts_are_retried() {
+    let silent = NameServer::start(|_, _| Reply::Never).await;
+    let mut resolver = TrustDnsResolver::builder()
+        .config(silent.config())
+        .timeout(Duration::from_millis(100))
+        .max_retries(2)
+        .backoff(Duration::from_millis(100))
+        .build()
+        .unwrap();
+
+    let name: Name = "www.google.com".parse().unwrap();
+    let start = Instant::now();
+    let result = resolver.call(name).await;
+
+    assert!(result.is_err());
+    // 3 attempts of 100ms and backoffs of 100ms and 200ms
+    assert!(start.elapsed() >= Duration::from_millis(600));
+}
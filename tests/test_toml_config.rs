@@ -0,0 +1,49 @@
+#[cfg(feature = "toml-config")]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use hyper_trust_dns::{ConfigError, ResolverTomlConfig, TrustDnsResolver};
+
+    #[test]
+    fn test_toml_config_parses() {
+        let config: ResolverTomlConfig = "[resolver]\nnameservers = [\"1.1.1.1:53\", \"1.0.0.1\"]\nprotocol = \"udp\"\ntimeout_ms = 2000\n"
+            .parse()
+            .unwrap();
+
+        let (config, options) = config.into_parts().unwrap();
+
+        // UDP and TCP for each nameserver
+        assert_eq!(config.name_servers().len(), 4);
+        assert_eq!(options.timeout, Duration::from_millis(2000));
+    }
+
+    #[test]
+    fn test_toml_config_rejects_invalid_config() {
+        assert!(matches!(
+            "[resolver]\nnameserver = [\"1.1.1.1\"]".parse::<ResolverTomlConfig>(),
+            Err(ConfigError::Parse(_))
+        ));
+
+        let config: ResolverTomlConfig = "[resolver]\nnameservers = []".parse().unwrap();
+        assert!(matches!(
+            config.into_parts(),
+            Err(ConfigError::MissingNameserver)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_from_toml_file_works() {
+        let path = env::temp_dir().join("hyper-trust-dns-test.toml");
+        fs::write(&path, "[resolver]\nnameservers = [\"1.1.1.1:53\"]\n").unwrap();
+
+        let resolver = TrustDnsResolver::from_toml_file(&path).unwrap();
+        assert!(format!("{resolver:?}").contains("1.1.1.1:53"));
+
+        fs::remove_file(path).unwrap();
+
+        assert!(matches!(
+            TrustDnsResolver::from_toml_file("/nonexistent.toml"),
+            Err(ConfigError::Io(_))
+        ));
+    }
+}